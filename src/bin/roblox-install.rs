@@ -0,0 +1,289 @@
+//! Prints where Roblox Studio is installed, for shell scripts.
+//!
+//! Built with the `cli` feature: `cargo install roblox_install --features cli`.
+
+use std::{
+    env,
+    error::Error as _,
+    fmt::Write as _,
+    path::{Path, PathBuf},
+    process,
+};
+
+use roblox_install::{Error, PluginDeploy, RobloxStudio};
+
+const USAGE: &str = "\
+Usage: roblox-install [--json] <command>
+
+Commands:
+    application         Print the path to the Roblox Studio executable
+    content             Print the path to the content directory
+    plugins             Print the path to the user plugins directory
+    built-in-plugins    Print the path to the built-in plugins directory
+    locate              Print every path of the installation
+    list                Print every installation that can be found
+    watch <source>      Deploy a plugin file or directory into the user plugins directory
+                        whenever it changes, until interrupted
+
+Options:
+    --json              Print JSON instead of text
+    -h, --help          Print this message
+    -V, --version       Print the version
+
+Roblox Studio is searched for like `RobloxStudio::locate` does, starting with the
+`ROBLOX_STUDIO_PATH` environment variable.
+
+Exit codes:
+    0    Success
+    1    Unknown error
+    2    Invalid arguments
+    10+  The error that stopped the search, listed in the README of roblox-install";
+
+/// The exit code of invalid arguments.
+const USAGE_ERROR: i32 = 2;
+
+#[derive(Clone, PartialEq, Eq)]
+enum Command {
+    Application,
+    Content,
+    Plugins,
+    BuiltInPlugins,
+    Locate,
+    List,
+    Watch(PathBuf),
+}
+
+fn main() {
+    let mut json = false;
+    let mut command = None;
+
+    let mut arguments = env::args().skip(1);
+
+    while let Some(argument) = arguments.next() {
+        match argument.as_str() {
+            "--json" => json = true,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return;
+            }
+            "-V" | "--version" => {
+                println!("roblox-install {}", env!("CARGO_PKG_VERSION"));
+                return;
+            }
+            _ if command.is_some() => usage_error(&format!("unexpected argument `{}`", argument)),
+            "application" => command = Some(Command::Application),
+            "content" => command = Some(Command::Content),
+            "plugins" => command = Some(Command::Plugins),
+            "built-in-plugins" => command = Some(Command::BuiltInPlugins),
+            "locate" => command = Some(Command::Locate),
+            "list" => command = Some(Command::List),
+            "watch" => match arguments.next() {
+                Some(source) => command = Some(Command::Watch(PathBuf::from(source))),
+                None => usage_error("missing source for `watch`"),
+            },
+            _ => usage_error(&format!("unknown command `{}`", argument)),
+        }
+    }
+
+    let command = command.unwrap_or_else(|| usage_error("missing command"));
+
+    if let Err(error) = run(command, json) {
+        eprintln!("error: {}", error_message(&error));
+        process::exit(exit_code(&error));
+    }
+}
+
+/// The message of an error followed by its causes.
+fn error_message(error: &Error) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+
+    while let Some(cause) = source {
+        let _ = write!(message, ": {}", cause);
+        source = cause.source();
+    }
+
+    message
+}
+
+fn run(command: Command, json: bool) -> Result<(), Error> {
+    if let Command::Watch(source) = &command {
+        return watch(source, json);
+    }
+
+    if command == Command::List {
+        let installs = RobloxStudio::locate_all();
+
+        if json {
+            let installs: Vec<String> = installs.iter().map(install_json).collect();
+            println!("[{}]", installs.join(","));
+        } else {
+            for install in &installs {
+                println!(
+                    "{} (from {})",
+                    install.application_path().display(),
+                    install.source()
+                );
+            }
+        }
+
+        return if installs.is_empty() {
+            Err(Error::NotInstalled)
+        } else {
+            Ok(())
+        };
+    }
+
+    let install = RobloxStudio::locate()?;
+
+    let path = match command {
+        Command::Application => install.application_path(),
+        Command::Content => install.content_path(),
+        Command::Plugins => install.plugins_path(),
+        Command::BuiltInPlugins => install.built_in_plugins_path(),
+        Command::Locate if json => {
+            println!("{}", install_json(&install));
+            return Ok(());
+        }
+        // `list` and `watch` were handled above
+        Command::Locate | Command::List | Command::Watch(_) => {
+            println!("application: {}", install.application_path().display());
+            println!("content: {}", install.content_path().display());
+            println!("plugins: {}", install.plugins_path().display());
+            println!(
+                "built-in-plugins: {}",
+                install.built_in_plugins_path().display()
+            );
+            println!("source: {}", install.source());
+            return Ok(());
+        }
+    };
+
+    if json {
+        println!("{}", path_json(path));
+    } else {
+        println!("{}", path.display());
+    }
+
+    Ok(())
+}
+
+/// Deploys a plugin until interrupted. Failed deploys are printed without stopping, as the
+/// source may be rewritten in the middle of a build.
+fn watch(source: &Path, json: bool) -> Result<(), Error> {
+    let name = match source.file_name() {
+        Some(name) => name.to_string_lossy(),
+        None => usage_error(&format!("`{}` is not a plugin", source.display())),
+    };
+
+    let install = RobloxStudio::locate()?;
+
+    install.watch_plugin(&name, source)?.watch(|event| {
+        match event {
+            Ok(deploy) if json => println!("{}", deploy_json(&deploy)),
+            Ok(deploy) => println!("{}", deploy),
+            Err(error) => eprintln!("error: {}", error_message(&error)),
+        }
+
+        true
+    });
+
+    Ok(())
+}
+
+fn usage_error(message: &str) -> ! {
+    eprintln!("error: {}\n\n{}", message, USAGE);
+    process::exit(USAGE_ERROR);
+}
+
+/// The exit code for each error. Codes are never reused, so scripts can rely on them.
+fn exit_code(error: &Error) -> i32 {
+    match error {
+        Error::DocumentsDirectoryNotFound => 10,
+        Error::MalformedRegistry => 11,
+        Error::PlatformNotSupported => 12,
+        Error::PluginsDirectoryNotFound => 13,
+        Error::RegistryError(_) => 14,
+        Error::EnvironmentVariableError(_) => 15,
+        Error::VinegarConfigError(_) => 16,
+        Error::NotInstalled => 17,
+        Error::RobloxDirectoryNotFound(_) => 18,
+        Error::VersionsDirectoryUnreadable { .. } => 19,
+        Error::NoVersionFound(_) => 20,
+        Error::StudioDirectoryNotFound(_) => 21,
+        Error::VersionUnreadable { .. } => 22,
+        Error::VersionNotFound(_) => 23,
+        Error::InvalidVersion(_) => 24,
+        Error::PackageManifestUnreadable { .. } => 25,
+        Error::MalformedPackageManifest(_) => 26,
+        Error::CorruptExecutable { .. } => 27,
+        Error::BundleInfoUnreadable { .. } => 28,
+        Error::PackageUnavailable { .. } => 29,
+        Error::PackageChecksumMismatch { .. } => 30,
+        Error::UnknownPackage(_) => 31,
+        Error::MalformedPackage { .. } => 32,
+        Error::InstallFailed { .. } => 33,
+        Error::VersionAlreadyInstalled(_) => 34,
+        Error::NotInVersionsDirectory(_) => 35,
+        Error::VersionRemovalFailed { .. } => 36,
+        Error::InvalidPluginName(_) => 37,
+        Error::PluginAlreadyInstalled(_) => 38,
+        Error::PluginNotInstalled(_) => 39,
+        Error::PluginInstallFailed { .. } => 40,
+        Error::PluginRemovalFailed { .. } => 41,
+        Error::PluginsDirectoryUnreadable { .. } => 42,
+        Error::PluginManifestUnreadable { .. } => 43,
+        Error::MalformedPluginManifest(_) => 44,
+        Error::PluginConflict(_) => 45,
+        Error::PluginLockfileUnwritable { .. } => 46,
+        Error::PluginSourceUnreadable { .. } => 47,
+        Error::NotAScript(_) => 48,
+        _ => 1,
+    }
+}
+
+fn install_json(install: &RobloxStudio) -> String {
+    format!(
+        "{{\"application\":{},\"content\":{},\"plugins\":{},\"built_in_plugins\":{},\"source\":{}}}",
+        path_json(install.application_path()),
+        path_json(install.content_path()),
+        path_json(install.plugins_path()),
+        path_json(install.built_in_plugins_path()),
+        string_json(&install.source().to_string()),
+    )
+}
+
+fn deploy_json(deploy: &PluginDeploy) -> String {
+    format!(
+        "{{\"path\":{},\"size\":{},\"number\":{}}}",
+        path_json(deploy.path()),
+        deploy.size(),
+        deploy.number(),
+    )
+}
+
+fn path_json(path: &Path) -> String {
+    string_json(&path.to_string_lossy())
+}
+
+fn string_json(text: &str) -> String {
+    let mut json = String::with_capacity(text.len() + 2);
+    json.push('"');
+
+    for character in text.chars() {
+        match character {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            character if character.is_control() => {
+                let _ = write!(json, "\\u{:04x}", character as u32);
+            }
+            character => json.push(character),
+        }
+    }
+
+    json.push('"');
+    json
+}
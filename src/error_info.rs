@@ -0,0 +1,133 @@
+use std::{
+    error::Error as _,
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use crate::Error;
+
+/// A serializable view of an [`Error`](enum@Error), to send it to another process or cache it
+/// along with located installations. Only available with the `serde` feature.
+///
+/// [`Error`](enum@Error) itself can't be serialized, as it holds
+/// [`io::Error`](std::io::Error)s.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ErrorInfo {
+    kind: String,
+    message: String,
+    path: Option<PathBuf>,
+    causes: Vec<String>,
+}
+
+impl ErrorInfo {
+    /// The name of the [`Error`](enum@Error) variant, such as `NoVersionFound`.
+    #[must_use]
+    #[inline]
+    pub fn kind(&self) -> &str {
+        &self.kind
+    }
+
+    /// The message of the error, as printed by its [`Display`](fmt::Display) implementation.
+    #[must_use]
+    #[inline]
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// The path the error is about, for the variants carrying one.
+    #[must_use]
+    #[inline]
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// The messages of the errors that caused this one, from the closest to the root cause.
+    #[must_use]
+    #[inline]
+    pub fn causes(&self) -> &[String] {
+        &self.causes
+    }
+}
+
+impl fmt::Display for ErrorInfo {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(&self.message)
+    }
+}
+
+impl From<&Error> for ErrorInfo {
+    fn from(error: &Error) -> Self {
+        let (kind, path): (&str, Option<&Path>) = match error {
+            Error::DocumentsDirectoryNotFound => ("DocumentsDirectoryNotFound", None),
+            Error::MalformedRegistry => ("MalformedRegistry", None),
+            Error::PlatformNotSupported => ("PlatformNotSupported", None),
+            Error::PluginsDirectoryNotFound => ("PluginsDirectoryNotFound", None),
+            Error::RegistryError(_) => ("RegistryError", None),
+            Error::EnvironmentVariableError(_) => ("EnvironmentVariableError", None),
+            Error::VinegarConfigError(_) => ("VinegarConfigError", None),
+            Error::NotInstalled => ("NotInstalled", None),
+            Error::RobloxDirectoryNotFound(path) => ("RobloxDirectoryNotFound", Some(path)),
+            Error::VersionsDirectoryUnreadable { path, .. } => {
+                ("VersionsDirectoryUnreadable", Some(path))
+            }
+            Error::NoVersionFound(path) => ("NoVersionFound", Some(path)),
+            Error::StudioDirectoryNotFound(path) => ("StudioDirectoryNotFound", Some(path)),
+            Error::VersionUnreadable { path, .. } => ("VersionUnreadable", Some(path)),
+            Error::VersionNotFound(path) => ("VersionNotFound", Some(path)),
+            Error::InvalidVersion(_) => ("InvalidVersion", None),
+            Error::PackageManifestUnreadable { path, .. } => {
+                ("PackageManifestUnreadable", Some(path))
+            }
+            Error::MalformedPackageManifest(_) => ("MalformedPackageManifest", None),
+            Error::CorruptExecutable { path, .. } => ("CorruptExecutable", Some(path)),
+            Error::BundleInfoUnreadable { path, .. } => ("BundleInfoUnreadable", Some(path)),
+            Error::PackageUnavailable { .. } => ("PackageUnavailable", None),
+            Error::PackageChecksumMismatch { .. } => ("PackageChecksumMismatch", None),
+            Error::UnknownPackage(_) => ("UnknownPackage", None),
+            Error::MalformedPackage { .. } => ("MalformedPackage", None),
+            Error::InstallFailed { path, .. } => ("InstallFailed", Some(path)),
+            Error::VersionAlreadyInstalled(path) => ("VersionAlreadyInstalled", Some(path)),
+            Error::NotInVersionsDirectory(path) => ("NotInVersionsDirectory", Some(path)),
+            Error::VersionRemovalFailed { path, .. } => ("VersionRemovalFailed", Some(path)),
+            Error::InvalidPluginName(_) => ("InvalidPluginName", None),
+            Error::PluginAlreadyInstalled(path) => ("PluginAlreadyInstalled", Some(path)),
+            Error::PluginNotInstalled(path) => ("PluginNotInstalled", Some(path)),
+            Error::PluginInstallFailed { path, .. } => ("PluginInstallFailed", Some(path)),
+            Error::PluginRemovalFailed { path, .. } => ("PluginRemovalFailed", Some(path)),
+            Error::PluginsDirectoryUnreadable { path, .. } => {
+                ("PluginsDirectoryUnreadable", Some(path))
+            }
+            Error::PluginManifestUnreadable { path, .. } => {
+                ("PluginManifestUnreadable", Some(path))
+            }
+            Error::MalformedPluginManifest(_) => ("MalformedPluginManifest", None),
+            Error::PluginConflict(path) => ("PluginConflict", Some(path)),
+            Error::PluginLockfileUnwritable { path, .. } => {
+                ("PluginLockfileUnwritable", Some(path))
+            }
+            Error::PluginSourceUnreadable { path, .. } => ("PluginSourceUnreadable", Some(path)),
+            Error::NotAScript(path) => ("NotAScript", Some(path)),
+        };
+
+        let mut causes = Vec::new();
+        let mut source = error.source();
+
+        while let Some(cause) = source {
+            causes.push(cause.to_string());
+            source = cause.source();
+        }
+
+        ErrorInfo {
+            kind: kind.to_owned(),
+            message: error.to_string(),
+            path: path.map(Path::to_owned),
+            causes,
+        }
+    }
+}
+
+impl From<Error> for ErrorInfo {
+    fn from(error: Error) -> Self {
+        ErrorInfo::from(&error)
+    }
+}
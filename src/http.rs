@@ -0,0 +1,164 @@
+use std::{
+    io::{self, Read, Write},
+    net::TcpStream,
+    time::Duration,
+};
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Downloads a file with a plain HTTP/1.1 `GET` request. This is only meant for package
+/// mirrors on the local network, so TLS and redirections are not supported.
+///
+/// A missing file is reported as [`io::ErrorKind::NotFound`].
+pub(crate) fn get(url: &str) -> io::Result<Vec<u8>> {
+    let address = url.strip_prefix("http://").ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("`{}` is not an `http://` URL", url),
+        )
+    })?;
+
+    let (host, path) = match address.find('/') {
+        Some(slash) => (&address[..slash], &address[slash..]),
+        None => (address, "/"),
+    };
+
+    let mut stream = connect(host)?;
+
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+
+    let request = format!(
+        "GET {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: roblox-install\r\nConnection: close\r\n\r\n",
+        path, host
+    );
+    stream.write_all(request.as_bytes())?;
+
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response)?;
+
+    parse_response(url, &response)
+}
+
+/// Connects to the host of a URL, on port 80 unless it has another one. IPv6 addresses are
+/// written in brackets (`[::1]:8080`), so their colons aren't taken for a port.
+fn connect(host: &str) -> io::Result<TcpStream> {
+    let invalid_host = || {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("`{}` is not a valid host", host),
+        )
+    };
+
+    let (address, port) = match host.strip_prefix('[') {
+        Some(bracketed) => match bracketed.split_once(']').ok_or_else(invalid_host)? {
+            (address, "") => (address, None),
+            (address, rest) => (
+                address,
+                Some(rest.strip_prefix(':').ok_or_else(invalid_host)?),
+            ),
+        },
+        None => match host.rsplit_once(':') {
+            Some((address, port)) => (address, Some(port)),
+            None => (host, None),
+        },
+    };
+
+    let port = match port {
+        Some(port) => port.parse().map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("`{}` is not a valid port", port),
+            )
+        })?,
+        None => 80,
+    };
+
+    TcpStream::connect((address, port))
+}
+
+fn parse_response(url: &str, response: &[u8]) -> io::Result<Vec<u8>> {
+    let invalid = || io::Error::new(io::ErrorKind::InvalidData, "invalid HTTP response");
+
+    let header_end = response
+        .windows(4)
+        .position(|window| window == b"\r\n\r\n")
+        .ok_or_else(invalid)?;
+    let header = std::str::from_utf8(&response[..header_end]).map_err(|_| invalid())?;
+    let body = &response[header_end + 4..];
+
+    let mut lines = header.split("\r\n");
+    let status: u16 = lines
+        .next()
+        .and_then(|status_line| status_line.split_whitespace().nth(1))
+        .and_then(|status| status.parse().ok())
+        .ok_or_else(invalid)?;
+
+    match status {
+        200 => {}
+        404 | 410 => {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("`{}` was not found", url),
+            ))
+        }
+        _ => {
+            return Err(io::Error::other(format!(
+                "`{}` answered with the status {}",
+                url, status
+            )))
+        }
+    }
+
+    let mut content_length = None;
+    let mut chunked = false;
+
+    for line in lines {
+        let (name, value) = match line.find(':') {
+            Some(colon) => (line[..colon].trim(), line[colon + 1..].trim()),
+            None => continue,
+        };
+
+        if name.eq_ignore_ascii_case("content-length") {
+            content_length = Some(value.parse::<usize>().map_err(|_| invalid())?);
+        } else if name.eq_ignore_ascii_case("transfer-encoding") {
+            chunked = value.eq_ignore_ascii_case("chunked");
+        }
+    }
+
+    if chunked {
+        return decode_chunked(body).ok_or_else(invalid);
+    }
+
+    match content_length {
+        Some(length) if body.len() < length => Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            format!("the download of `{}` was cut short", url),
+        )),
+        Some(length) => Ok(body[..length].to_vec()),
+        None => Ok(body.to_vec()),
+    }
+}
+
+/// Decodes a body sent with `Transfer-Encoding: chunked`, where each chunk is preceded by
+/// its size in hexadecimal.
+fn decode_chunked(mut body: &[u8]) -> Option<Vec<u8>> {
+    let mut decoded = Vec::new();
+
+    loop {
+        let line_end = body.windows(2).position(|window| window == b"\r\n")?;
+        let size_line = std::str::from_utf8(&body[..line_end]).ok()?;
+
+        // chunk extensions follow the size after a semicolon
+        let size_text = size_line.split(';').next()?.trim();
+        let size = usize::from_str_radix(size_text, 16).ok()?;
+        body = &body[line_end + 2..];
+
+        if size == 0 {
+            return Some(decoded);
+        }
+
+        decoded.extend_from_slice(body.get(..size)?);
+        body = body.get(size + 2..)?;
+    }
+}
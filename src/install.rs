@@ -0,0 +1,284 @@
+use std::{
+    fs::{self, File},
+    io,
+    path::{Path, PathBuf},
+};
+
+use zip::ZipArchive;
+
+use crate::{
+    http,
+    manifest::{PackageManifest, MANIFEST_FILE_NAME},
+    Error, Platform, Result, RobloxStudio, StudioVersion,
+};
+
+/// The `AppSettings.xml` the bootstrapper writes next to `RobloxStudioBeta.exe`, which tells
+/// it where its `content` directory is.
+const APP_SETTINGS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Settings>
+	<ContentFolder>content</ContentFolder>
+	<BaseUrl>http://www.roblox.com</BaseUrl>
+</Settings>
+"#;
+
+/// Where an [`Installer`] gets the packages of a version from.
+///
+/// Files are looked up with the version as a prefix first, like on the Roblox deployment
+/// servers (`version-<hash>-rbxPkgManifest.txt`), and then without it
+/// (`rbxPkgManifest.txt`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PackageSource {
+    /// A directory holding the `rbxPkgManifest.txt` of the version and its package zips.
+    Directory(PathBuf),
+
+    /// The base URL of a package mirror served over plain HTTP, such as
+    /// `http://mirror.local:8080/roblox`. HTTPS is not supported.
+    Http(String),
+}
+
+/// Installs a Roblox Studio version from a package mirror, for machines that can't run the
+/// official bootstrapper, such as Linux and CI machines.
+///
+/// Every package listed in the version's `rbxPkgManifest.txt` is checked against its MD5
+/// checksum and extracted where Roblox Studio expects it. The version is put together in a
+/// temporary directory and only moved into `Versions` once complete, so an interrupted
+/// installation never leaves a broken version behind.
+///
+/// ```no_run
+/// use roblox_install::Installer;
+///
+/// let studio = Installer::from_mirror("http://mirror.local:8080/roblox", "version-1a2b3c4d")
+///     .install("/opt/roblox")?;
+///
+/// println!("Installed {}", studio.application_path().display());
+/// # Ok::<(), roblox_install::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+#[must_use]
+pub struct Installer {
+    source: PackageSource,
+    version: String,
+}
+
+impl Installer {
+    /// Creates an installer for the given version, such as `version-1a2b3c4d`.
+    pub fn new<V: Into<String>>(source: PackageSource, version: V) -> Self {
+        Installer {
+            source,
+            version: version.into(),
+        }
+    }
+
+    /// Creates an installer getting the packages from a local directory.
+    pub fn from_directory<P: Into<PathBuf>, V: Into<String>>(directory: P, version: V) -> Self {
+        Self::new(PackageSource::Directory(directory.into()), version)
+    }
+
+    /// Creates an installer getting the packages from an HTTP mirror.
+    pub fn from_mirror<U: Into<String>, V: Into<String>>(url: U, version: V) -> Self {
+        Self::new(PackageSource::Http(url.into()), version)
+    }
+
+    /// Installs the version in the `Versions` directory of the given `Roblox` directory, and
+    /// returns it. The `Roblox` directory can then be given to
+    /// [`RobloxStudio::locate_in`] or the `ROBLOX_STUDIO_PATH` environment variable.
+    ///
+    /// Fails with [`Error::InvalidVersion`] when the version isn't the name of a version
+    /// directory, with [`Error::VersionAlreadyInstalled`] when it is already installed, and
+    /// with [`Error::PackageUnavailable`], [`Error::PackageChecksumMismatch`],
+    /// [`Error::UnknownPackage`] or [`Error::MalformedPackage`] when a package can't be used.
+    /// A package that doesn't have the size listed in the manifest is unavailable, as its
+    /// download was cut short.
+    pub fn install<P: AsRef<Path>>(&self, roblox: P) -> Result<RobloxStudio> {
+        let roblox = roblox.as_ref();
+
+        let is_version_directory = self
+            .version
+            .parse::<StudioVersion>()
+            .is_ok_and(|version| version.hash().is_some());
+
+        if !is_version_directory {
+            return Err(Error::InvalidVersion(self.version.clone()));
+        }
+
+        let version = roblox.join("Versions").join(&self.version);
+
+        if version.exists() {
+            return Err(Error::VersionAlreadyInstalled(version));
+        }
+
+        // the version is put together outside of `Versions`, so it is never found while
+        // incomplete
+        let staging = roblox.join(format!(".{}.partial", self.version));
+
+        if let Err(error) = self.install_packages(&staging) {
+            let _ = fs::remove_dir_all(&staging);
+            return Err(error);
+        }
+
+        create_dir_all(&roblox.join("Versions"))?;
+        fs::rename(&staging, &version).map_err(|source| Error::InstallFailed {
+            path: version.clone(),
+            source,
+        })?;
+
+        RobloxStudio::locate_in(version, Platform::Windows)
+    }
+
+    fn install_packages(&self, staging: &Path) -> Result<()> {
+        if staging.exists() {
+            // left behind by an installation that was interrupted
+            fs::remove_dir_all(staging).map_err(|source| Error::InstallFailed {
+                path: staging.to_owned(),
+                source,
+            })?;
+        }
+
+        create_dir_all(staging)?;
+
+        let manifest_data = self.fetch(MANIFEST_FILE_NAME)?;
+        let manifest = PackageManifest::parse(&String::from_utf8_lossy(&manifest_data))?;
+
+        for package in manifest.packages() {
+            let destination = package
+                .destination()
+                .ok_or_else(|| Error::UnknownPackage(package.name().to_owned()))?;
+
+            let data = self.fetch(package.name())?;
+
+            // a short read is a download cut short, rather than a package that doesn't match
+            if data.len() as u64 != package.packed_size() {
+                let kind = if (data.len() as u64) < package.packed_size() {
+                    io::ErrorKind::UnexpectedEof
+                } else {
+                    io::ErrorKind::InvalidData
+                };
+
+                return Err(Error::PackageUnavailable {
+                    package: package.name().to_owned(),
+                    source: io::Error::new(
+                        kind,
+                        format!(
+                            "got {} bytes instead of the {} of the manifest",
+                            data.len(),
+                            package.packed_size()
+                        ),
+                    ),
+                });
+            }
+
+            let checksum = format!("{:x}", md5::compute(&data));
+
+            if checksum != package.checksum() {
+                return Err(Error::PackageChecksumMismatch {
+                    package: package.name().to_owned(),
+                    expected: package.checksum().to_owned(),
+                    actual: checksum,
+                });
+            }
+
+            extract(package.name(), &data, &staging.join(destination))?;
+        }
+
+        create_dir_all(&staging.join("content"))?;
+        write(&staging.join(MANIFEST_FILE_NAME), &manifest_data)?;
+        write(&staging.join("AppSettings.xml"), APP_SETTINGS.as_bytes())
+    }
+
+    /// Gets a file of the version, with the version as a prefix first.
+    fn fetch(&self, name: &str) -> Result<Vec<u8>> {
+        let prefixed = format!("{}-{}", self.version, name);
+
+        let result = match self.fetch_file(&prefixed) {
+            Err(error) if error.kind() == io::ErrorKind::NotFound => self.fetch_file(name),
+            result => result,
+        };
+
+        result.map_err(|source| Error::PackageUnavailable {
+            package: name.to_owned(),
+            source,
+        })
+    }
+
+    fn fetch_file(&self, name: &str) -> io::Result<Vec<u8>> {
+        match &self.source {
+            PackageSource::Directory(directory) => fs::read(directory.join(name)),
+            PackageSource::Http(url) => {
+                http::get(&format!("{}/{}", url.trim_end_matches('/'), name))
+            }
+        }
+    }
+}
+
+/// Extracts a package zip. Paths in Roblox packages can use either slashes or backslashes.
+fn extract(package: &str, data: &[u8], destination: &Path) -> Result<()> {
+    let malformed = |reason: String| Error::MalformedPackage {
+        package: package.to_owned(),
+        reason,
+    };
+
+    let mut archive =
+        ZipArchive::new(io::Cursor::new(data)).map_err(|error| malformed(error.to_string()))?;
+
+    for index in 0..archive.len() {
+        let mut entry = archive
+            .by_index(index)
+            .map_err(|error| malformed(error.to_string()))?;
+
+        let name = entry.name().to_owned();
+        let relative = entry_path(&name)
+            .ok_or_else(|| malformed(format!("`{}` is outside of the package", name)))?;
+        let path = destination.join(relative);
+
+        if name.ends_with(['/', '\\']) {
+            create_dir_all(&path)?;
+            continue;
+        }
+
+        if let Some(parent) = path.parent() {
+            create_dir_all(parent)?;
+        }
+
+        let install_failed = |source| Error::InstallFailed {
+            path: path.clone(),
+            source,
+        };
+
+        let mut file = File::create(&path).map_err(install_failed)?;
+        io::copy(&mut entry, &mut file).map_err(install_failed)?;
+    }
+
+    Ok(())
+}
+
+/// The path of a zip entry relative to where the package is extracted, or `None` when it
+/// would end up outside of it.
+fn entry_path(name: &str) -> Option<PathBuf> {
+    let mut path = PathBuf::new();
+
+    for component in name.split(['/', '\\']) {
+        match component {
+            "" | "." => {}
+            ".." => return None,
+            _ if component.contains(':') => return None,
+            _ => path.push(component),
+        }
+    }
+
+    Some(path)
+}
+
+fn create_dir_all(path: &Path) -> Result<()> {
+    fs::create_dir_all(path).map_err(|source| Error::InstallFailed {
+        path: path.to_owned(),
+        source,
+    })
+}
+
+fn write(path: &Path, contents: &[u8]) -> Result<()> {
+    fs::write(path, contents).map_err(|source| Error::InstallFailed {
+        path: path.to_owned(),
+        source,
+    })
+}
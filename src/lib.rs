@@ -1,19 +1,23 @@
 use std::{
-    env, fs, io,
+    env, fmt, fs, io,
     path::{Path, PathBuf},
+    time::SystemTime,
 };
 
 use thiserror::Error;
 
-#[cfg(target_os = "windows")]
-use winreg::RegKey;
-
 /// A wrapper for [`Result`](https://doc.rust-lang.org/std/result/enum.Result.html) that
 /// contains [`Error`] in the `Err` type.
 pub type Result<T> = std::result::Result<T, Error>;
 
 const ROBLOX_STUDIO_PATH_VARIABLE: &str = "ROBLOX_STUDIO_PATH";
 
+/// The registry key, relative to `HKEY_CURRENT_USER`, Roblox Studio stores its location in.
+const ROBLOX_STUDIO_KEY: &str = r"Software\Roblox\RobloxStudio";
+
+/// The identifier of the Roblox Studio bundle on macOS.
+const ROBLOX_STUDIO_BUNDLE_IDENTIFIER: &str = "com.roblox.RobloxStudio";
+
 #[derive(Debug, Error)]
 #[non_exhaustive]
 /// Everything that can go wrong while using roblox-install.
@@ -36,11 +40,336 @@ pub enum Error {
     #[error("Environment variable misconfigured: {0}")]
     EnvironmentVariableError(String),
 
+    #[error("Vinegar configuration misconfigured: {0}")]
+    VinegarConfigError(String),
+
     #[error("Couldn't find Roblox Studio")]
     NotInstalled,
+
+    #[error("Found a `content` directory in `{}`, but no Roblox directory above it", .0.display())]
+    RobloxDirectoryNotFound(PathBuf),
+
+    #[error("Couldn't read the Versions directory `{}`", .path.display())]
+    VersionsDirectoryUnreadable {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+
+    #[error("Couldn't find any Roblox Studio version in `{}`", .0.display())]
+    NoVersionFound(PathBuf),
+
+    #[error("`{}` contains neither a `content` nor a `Versions` directory", .0.display())]
+    StudioDirectoryNotFound(PathBuf),
+
+    #[error("Couldn't read the version of Roblox Studio from `{}`", .path.display())]
+    VersionUnreadable {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+
+    #[error("Couldn't find the version of the Roblox Studio installed in `{}`", .0.display())]
+    VersionNotFound(PathBuf),
+
+    #[error("`{0}` is not a Roblox Studio version")]
+    InvalidVersion(String),
+
+    #[error("Couldn't read the package manifest `{}`", .path.display())]
+    PackageManifestUnreadable {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+
+    #[error("Malformed package manifest: {0}")]
+    MalformedPackageManifest(String),
+
+    #[error("`{}` is not a valid executable: {reason}", .path.display())]
+    CorruptExecutable { path: PathBuf, reason: String },
+
+    #[error("Couldn't read the bundle information `{}`", .path.display())]
+    BundleInfoUnreadable {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+
+    #[error("Couldn't get the package `{package}`")]
+    PackageUnavailable {
+        package: String,
+        #[source]
+        source: io::Error,
+    },
+
+    #[error("The package `{package}` has the checksum {actual} instead of {expected}")]
+    PackageChecksumMismatch {
+        package: String,
+        expected: String,
+        actual: String,
+    },
+
+    #[error("Don't know where to extract the package `{0}`")]
+    UnknownPackage(String),
+
+    #[error("The package `{package}` is malformed: {reason}")]
+    MalformedPackage { package: String, reason: String },
+
+    #[error("Couldn't write `{}` while installing Roblox Studio", .path.display())]
+    InstallFailed {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+
+    #[error("`{}` is already installed", .0.display())]
+    VersionAlreadyInstalled(PathBuf),
+
+    #[error("`{}` is not inside a Versions directory", .0.display())]
+    NotInVersionsDirectory(PathBuf),
+
+    #[error("Couldn't remove the version directory `{}`", .path.display())]
+    VersionRemovalFailed {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+
+    #[error("`{0}` is not a valid plugin file name")]
+    InvalidPluginName(String),
+
+    #[error("The plugin `{}` is already installed", .0.display())]
+    PluginAlreadyInstalled(PathBuf),
+
+    #[error("The plugin `{}` is not installed", .0.display())]
+    PluginNotInstalled(PathBuf),
+
+    #[error("Couldn't install the plugin `{}`", .path.display())]
+    PluginInstallFailed {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+
+    #[error("Couldn't remove the plugin `{}`", .path.display())]
+    PluginRemovalFailed {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+
+    #[error("Couldn't read the plugins directory `{}`", .path.display())]
+    PluginsDirectoryUnreadable {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+
+    #[error("Couldn't read the plugin manifest `{}`", .path.display())]
+    PluginManifestUnreadable {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+
+    #[error("Malformed plugin manifest: {0}")]
+    MalformedPluginManifest(String),
+
+    #[error("The plugin `{}` was not installed from this plugin manifest", .0.display())]
+    PluginConflict(PathBuf),
+
+    #[error("Couldn't write the plugin lockfile `{}`", .path.display())]
+    PluginLockfileUnwritable {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+
+    #[error("Couldn't read the plugin source `{}`", .path.display())]
+    PluginSourceUnreadable {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+
+    #[error("`{}` is not a Luau script", .0.display())]
+    NotAScript(PathBuf),
+}
+
+/// Where a located Roblox Studio installation was found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum StudioSource {
+    /// The directory pointed at by the `ROBLOX_STUDIO_PATH` environment variable.
+    EnvironmentVariable,
+
+    /// The `ContentFolder` value of the `HKCU\Software\Roblox\RobloxStudio` registry key.
+    Registry,
+
+    /// The platform's default installation directory (`%LOCALAPPDATA%\Roblox` on Windows,
+    /// `/Applications` and `~/Applications` on macOS).
+    DefaultLocation,
+
+    /// A directory given explicitly to a [`StudioLocator`]. It follows the same rules as
+    /// the `ROBLOX_STUDIO_PATH` environment variable.
+    Directory(PathBuf),
+
+    /// A Wine prefix, searched for Roblox Studio in the local application data of each of
+    /// its users (`drive_c/users/<user>/AppData/Local/Roblox`).
+    WinePrefix(PathBuf),
+
+    /// A [Vinegar](https://vinegarhq.org) installation, given by its data directory
+    /// (`~/.local/share/vinegar`) and configuration file (`~/.config/vinegar/config.toml`).
+    /// Vinegar runs Roblox Studio under Wine on Linux, in a prefix of its own.
+    Vinegar { data: PathBuf, config: PathBuf },
+}
+
+impl fmt::Display for StudioSource {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StudioSource::EnvironmentVariable => write!(
+                formatter,
+                "the `{}` environment variable",
+                ROBLOX_STUDIO_PATH_VARIABLE
+            ),
+            StudioSource::Registry => write!(formatter, "the registry"),
+            StudioSource::DefaultLocation => write!(formatter, "the default location"),
+            StudioSource::Directory(root) => write!(formatter, "`{}`", root.display()),
+            StudioSource::WinePrefix(prefix) => {
+                write!(formatter, "the Wine prefix `{}`", prefix.display())
+            }
+            StudioSource::Vinegar { data, .. } => {
+                write!(formatter, "the Vinegar installation in `{}`", data.display())
+            }
+        }
+    }
+}
+
+/// The layout of a Roblox Studio installation, which decides where its files are found in a
+/// directory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum Platform {
+    /// `RobloxStudioBeta.exe` next to the `content` and `BuiltInPlugins` directories, in a
+    /// version directory inside `Roblox\Versions`. User plugins are in `Roblox\Plugins`. This
+    /// is also the layout under Wine and when reaching a Windows installation from WSL.
+    Windows,
+
+    /// A `RobloxStudio.app` bundle. User plugins are in `~/Documents/Roblox/Plugins`.
+    MacOs,
+}
+
+impl Platform {
+    /// The layout used for directories when none is given: [`Platform::MacOs`] on macOS, and
+    /// [`Platform::Windows`] everywhere else, as Roblox Studio can only run through Wine or
+    /// WSL on other platforms.
+    #[must_use]
+    pub fn current() -> Platform {
+        if cfg!(target_os = "macos") {
+            Platform::MacOs
+        } else {
+            Platform::Windows
+        }
+    }
+}
+
+impl Default for Platform {
+    fn default() -> Self {
+        Self::current()
+    }
+}
+
+/// How to pick a version when a Roblox directory contains several folders in `Versions`.
+/// Roblox Studio updates commonly leave old version folders behind, so the order in which
+/// the file system lists them cannot be relied on.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum VersionSelection {
+    /// Picks the version whose `RobloxStudioBeta.exe` was modified most recently. Versions
+    /// modified at the same time are ordered by folder name. This is the default.
+    #[default]
+    NewestExecutable,
+
+    /// Picks the version the `ContentFolder` registry value points at. For a Wine prefix, the
+    /// registry saved in its `user.reg` file is used. Falls back to
+    /// [`VersionSelection::NewestExecutable`] when the registry can't be read or points
+    /// at a folder that isn't in `Versions`.
+    RegistryContentFolder,
 }
 
+impl VersionSelection {
+    /// Sorts the installations from most to least preferred.
+    fn sort(self, installs: &mut [RobloxStudio]) {
+        installs.sort_by(|a, b| {
+            b.application_modified()
+                .cmp(&a.application_modified())
+                .then_with(|| a.root.cmp(&b.root))
+        });
+
+        if self == VersionSelection::RegistryContentFolder {
+            let registry_content = installs
+                .first()
+                .and_then(|install| RobloxStudio::registry_content_folder_for(&install.source));
+
+            if let Some(content) = registry_content {
+                let index = installs
+                    .iter()
+                    .position(|install| install.content == content);
+
+                if let Some(index) = index {
+                    installs[..=index].rotate_right(1);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+mod error_info;
+mod http;
+mod install;
+mod locator;
+mod manifest;
+mod model;
+mod pe;
+mod plist;
+mod plugin_sync;
+mod plugins;
+mod prune;
+mod registry;
+mod report;
+mod usage;
+mod version;
+mod verify;
+mod vinegar;
+mod watch;
+mod wine;
+
+#[cfg(feature = "serde")]
+pub use error_info::ErrorInfo;
+pub use install::{Installer, PackageSource};
+pub use locator::StudioLocator;
+pub use manifest::{Package, PackageManifest};
+pub use model::{InstanceClass, ModelInstance, PluginModel};
+pub use pe::VersionResource;
+pub use plist::BundleInfo;
+pub use plugin_sync::{PluginChange, PluginManifest, PluginSyncReport};
+pub use plugins::{Plugin, PluginIssue, PluginKind, PluginSource};
+pub use prune::{PruneReport, VersionDirectory};
+#[cfg(target_os = "windows")]
+pub use registry::WindowsRegistry;
+pub use registry::{MemoryRegistry, RegistrySource, WineRegistry};
+pub use report::{LocateReport, LocateStep};
+pub use usage::DiskUsage;
+pub use verify::{IntegrityIssue, IntegrityReport};
+pub use version::StudioVersion;
+pub use watch::{PluginDeploy, PluginWatcher};
+
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[must_use]
 pub struct RobloxStudio {
     content: PathBuf,
@@ -48,6 +377,8 @@ pub struct RobloxStudio {
     built_in_plugins: PathBuf,
     plugins: PathBuf,
     root: PathBuf,
+    source: StudioSource,
+    platform: Platform,
 }
 
 impl RobloxStudio {
@@ -59,30 +390,213 @@ impl RobloxStudio {
     /// the `RobloxStudioBeta.exe` file and `content` directory are located) or it
     /// can also point to the Roblox directory in AppData (`$APPDATA\Local\Roblox`)
     /// and it will find the latest version by itself.
+    ///
+    /// On macOS, the environment variable can point to a bundle or to a directory containing
+    /// it. Without it, Roblox Studio is searched for in `/Applications`, then in
+    /// `~/Applications` where it is installed without administrator rights.
+    ///
+    /// On Linux, it will look for Roblox Studio running under Wine, in the prefix pointed at
+    /// by `$WINEPREFIX` or in `~/.wine` by default.
+    ///
+    /// When several versions are found, the one picked is decided by the default
+    /// [`VersionSelection`]. Use a [`StudioLocator`] to change where Roblox Studio is
+    /// searched for.
     pub fn locate() -> Result<RobloxStudio> {
-        Self::locate_from_env().unwrap_or_else(Self::locate_target_specific)
+        StudioLocator::new().locate()
+    }
+
+    /// Same as [`RobloxStudio::locate`], but uses the given [`VersionSelection`] to pick
+    /// a version when several are found.
+    pub fn locate_with_selection(selection: VersionSelection) -> Result<RobloxStudio> {
+        StudioLocator::new().version_selection(selection).locate()
+    }
+
+    /// Same as [`RobloxStudio::locate`], but also returns a [`LocateReport`] of every
+    /// location that was checked. See [`StudioLocator::locate_with_report`] for details.
+    pub fn locate_with_report() -> (Result<RobloxStudio>, LocateReport) {
+        StudioLocator::new().locate_with_report()
+    }
+
+    /// Finds Roblox Studio in the given directory by applying the layout rules of `platform`,
+    /// whichever platform this runs on. With [`Platform::Windows`], `root` can be a version
+    /// directory or a `Roblox` directory containing `Versions`, like the `ROBLOX_STUDIO_PATH`
+    /// environment variable. With [`Platform::MacOs`], `root` can be a bundle, or a directory
+    /// such as `Applications` in which bundles are recognized by their identifier whatever
+    /// their name.
+    ///
+    /// The installation is tagged with [`StudioSource::Directory`].
+    pub fn locate_in<P: Into<PathBuf>>(root: P, platform: Platform) -> Result<RobloxStudio> {
+        StudioLocator::empty()
+            .platform(platform)
+            .directory(root)
+            .locate()
+    }
+
+    /// Finds every Roblox Studio installation that can be located, instead of stopping
+    /// at the first one like [`RobloxStudio::locate`] does. See
+    /// [`StudioLocator::locate_all`] for details.
+    #[must_use]
+    pub fn locate_all() -> Vec<RobloxStudio> {
+        StudioLocator::new().locate_all()
+    }
+
+    /// Looks for installations in a single source. Returns `None` when the source does not
+    /// apply, such as when the environment variable is not defined or when the platform has
+    /// no registry.
+    ///
+    /// The environment variable and explicit directories are searched with the layout rules of
+    /// `platform`. Wine prefixes and Vinegar always use the Windows layout.
+    fn candidates_from_source(
+        source: &StudioSource,
+        platform: Platform,
+        report: &mut LocateReport,
+    ) -> Option<Result<Vec<RobloxStudio>>> {
+        match source {
+            StudioSource::EnvironmentVariable => {
+                let result = Self::env_root(report)?.and_then(|root| {
+                    Self::candidates_from_directory(
+                        root,
+                        StudioSource::EnvironmentVariable,
+                        platform,
+                        report,
+                    )
+                });
+
+                Some(result)
+            }
+            StudioSource::Registry => Self::candidates_from_registry(report),
+            StudioSource::DefaultLocation => Self::default_location_candidates(report),
+            StudioSource::Directory(root) => Some(Self::candidates_from_directory(
+                root.clone(),
+                source.clone(),
+                platform,
+                report,
+            )),
+            StudioSource::WinePrefix(prefix) => {
+                Some(Self::candidates_from_wine_prefix(prefix, source, report))
+            }
+            StudioSource::Vinegar { data, config } => {
+                Some(Self::candidates_from_vinegar(data, config, source, report))
+            }
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    fn candidates_from_registry(report: &mut LocateReport) -> Option<Result<Vec<RobloxStudio>>> {
+        Some(
+            Self::from_registry(&WindowsRegistry, StudioSource::Registry, report)
+                .map(|install| vec![install]),
+        )
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[inline]
+    fn candidates_from_registry(report: &mut LocateReport) -> Option<Result<Vec<RobloxStudio>>> {
+        report.push(LocateStep::SourceNotApplicable {
+            source: StudioSource::Registry,
+            reason: "there is no registry on this platform".to_owned(),
+        });
+
+        None
     }
 
     #[cfg(target_os = "windows")]
-    fn locate_target_specific() -> Result<RobloxStudio> {
-        let hkcu = RegKey::predef(winreg::enums::HKEY_CURRENT_USER);
+    fn default_location_candidates(
+        report: &mut LocateReport,
+    ) -> Option<Result<Vec<RobloxStudio>>> {
+        let local_data = match dirs::data_local_dir() {
+            Some(local_data) => local_data,
+            None => {
+                report.push(LocateStep::SourceNotApplicable {
+                    source: StudioSource::DefaultLocation,
+                    reason: "the local application data directory is unknown".to_owned(),
+                });
+
+                return None;
+            }
+        };
 
-        let roblox_studio_reg = hkcu
-            .open_subkey(r"Software\Roblox\RobloxStudio")
-            .map_err(Error::RegistryError)?;
+        Some(Self::candidates_from_directory(
+            local_data.join("Roblox"),
+            StudioSource::DefaultLocation,
+            Platform::Windows,
+            report,
+        ))
+    }
 
-        let content_folder_value: String = roblox_studio_reg
-            .get_value("ContentFolder")
-            .map_err(Error::RegistryError)?;
+    /// Finds Roblox Studio through the `ContentFolder` value of the
+    /// `HKCU\Software\Roblox\RobloxStudio` registry key, read from the given registry. The
+    /// installation is tagged with [`StudioSource::Registry`].
+    ///
+    /// Fails with [`Error::RegistryError`] when the value can't be read, and with
+    /// [`Error::MalformedRegistry`] when it isn't the `content` directory of a version in a
+    /// `Versions` directory.
+    pub fn locate_from_registry<R: RegistrySource + ?Sized>(registry: &R) -> Result<RobloxStudio> {
+        Self::from_registry(registry, StudioSource::Registry, &mut LocateReport::default())
+    }
+
+    fn from_registry<R: RegistrySource + ?Sized>(
+        registry: &R,
+        source: StudioSource,
+        report: &mut LocateReport,
+    ) -> Result<RobloxStudio> {
+        let content_folder_path = Self::registry_content_folder(registry, report)?;
+        Self::from_registry_content_folder(content_folder_path, source)
+    }
+
+    fn registry_content_folder<R: RegistrySource + ?Sized>(
+        registry: &R,
+        report: &mut LocateReport,
+    ) -> Result<PathBuf> {
+        let content_folder_value = registry.string_value(ROBLOX_STUDIO_KEY, "ContentFolder");
+
+        report.push(LocateStep::RegistryValue {
+            key: ROBLOX_STUDIO_KEY.to_owned(),
+            name: "ContentFolder".to_owned(),
+            value: content_folder_value.as_ref().ok().cloned(),
+        });
+
+        registry
+            .host_path(&content_folder_value.map_err(Error::RegistryError)?)
+            .ok_or(Error::MalformedRegistry)
+    }
 
-        let content_folder_path = PathBuf::from(content_folder_value);
+    /// The content folder the registry points at for installations found through the given
+    /// source.
+    fn registry_content_folder_for(source: &StudioSource) -> Option<PathBuf> {
+        let mut report = LocateReport::default();
 
+        match source {
+            StudioSource::WinePrefix(prefix) => {
+                Self::registry_content_folder(&WineRegistry::open(prefix).ok()?, &mut report).ok()
+            }
+
+            #[cfg(target_os = "windows")]
+            _ => Self::registry_content_folder(&WindowsRegistry, &mut report).ok(),
+
+            #[cfg(not(target_os = "windows"))]
+            _ => None,
+        }
+    }
+
+    /// Builds an installation from the `ContentFolder` registry value, which is the `content`
+    /// directory of a version.
+    fn from_registry_content_folder(
+        content_folder_path: PathBuf,
+        source: StudioSource,
+    ) -> Result<RobloxStudio> {
         let root = content_folder_path
             .parent()
             .ok_or(Error::MalformedRegistry)?
             .to_path_buf();
 
-        let plugins = Self::locate_plugins_on_windows()?;
+        let roblox_root = root
+            .parent()
+            .and_then(Path::parent)
+            .ok_or(Error::MalformedRegistry)?
+            .to_path_buf();
+
+        let plugins = Self::locate_plugins_on_windows(roblox_root)?;
 
         Ok(RobloxStudio {
             content: content_folder_path,
@@ -90,10 +604,11 @@ impl RobloxStudio {
             built_in_plugins: root.join("BuiltInPlugins"),
             plugins,
             root,
+            source,
+            platform: Platform::Windows,
         })
     }
 
-    #[cfg(not(target_os = "macos"))]
     fn locate_plugins_on_windows(root: PathBuf) -> Result<PathBuf> {
         let mut plugin_dir = root;
         plugin_dir.push("Plugins");
@@ -101,96 +616,335 @@ impl RobloxStudio {
     }
 
     #[cfg(target_os = "macos")]
-    fn locate_target_specific() -> Result<RobloxStudio> {
-        let mut root = PathBuf::from("/Applications");
-        root.push("RobloxStudio.app");
-        Self::locate_from_directory(root)
+    fn default_location_candidates(
+        report: &mut LocateReport,
+    ) -> Option<Result<Vec<RobloxStudio>>> {
+        let mut directories = vec![PathBuf::from("/Applications")];
+        directories.extend(dirs::home_dir().map(|home| home.join("Applications")));
+
+        let mut installs = Vec::new();
+        let mut first_error = None;
+
+        for directory in directories {
+            let source = StudioSource::DefaultLocation;
+
+            match Self::candidates_from_directory(directory, source, Platform::MacOs, report) {
+                Ok(found) => installs.extend(found),
+                Err(error) => {
+                    first_error.get_or_insert(error);
+                }
+            }
+        }
+
+        if installs.is_empty() {
+            Some(Err(first_error.unwrap_or(Error::NotInstalled)))
+        } else {
+            Some(Ok(installs))
+        }
     }
 
     #[cfg(not(any(target_os = "windows", target_os = "macos")))]
     #[inline]
-    fn locate_target_specific() -> Result<RobloxStudio> {
-        Err(Error::PlatformNotSupported)
+    fn default_location_candidates(
+        report: &mut LocateReport,
+    ) -> Option<Result<Vec<RobloxStudio>>> {
+        // Roblox Studio has no native installation here, it can only be found through Wine
+        report.push(LocateStep::SourceNotApplicable {
+            source: StudioSource::DefaultLocation,
+            reason: "Roblox Studio can only run through Wine on this platform".to_owned(),
+        });
+
+        None
     }
 
-    #[cfg(target_os = "windows")]
-    fn locate_from_directory(root: PathBuf) -> Result<RobloxStudio> {
-        Self::locate_from_windows_directory(root)
+    fn candidates_from_wine_prefix(
+        prefix: &Path,
+        source: &StudioSource,
+        report: &mut LocateReport,
+    ) -> Result<Vec<RobloxStudio>> {
+        // the prefix's registry points at one version, the `Versions` directories of the
+        // prefix's users are scanned for the others, or when the key is missing. Like any other
+        // candidates, they are then ordered by the `VersionSelection`.
+        let user_registry = prefix.join("user.reg");
+        let mut installs = Vec::new();
+
+        match WineRegistry::open(prefix) {
+            Ok(registry) => {
+                report.push(LocateStep::FileRead(user_registry));
+
+                if let Ok(install) = Self::from_registry(&registry, source.clone(), report) {
+                    if install.application.is_file() {
+                        report.push(LocateStep::CandidateFound(install.application.clone()));
+                        installs.push(install);
+                    } else {
+                        report.push(LocateStep::MissingFile(install.application));
+                    }
+                }
+            }
+            Err(_) => report.push(LocateStep::MissingFile(user_registry)),
+        }
+
+        for roblox in wine::roblox_directories(prefix) {
+            report.push(LocateStep::CandidateDirectory(roblox.clone()));
+
+            let found =
+                match Self::candidates_from_windows_directory(roblox, source.clone(), report) {
+                    Ok(found) => found,
+                    Err(_) => continue,
+                };
+
+            for install in found {
+                if installs
+                    .iter()
+                    .any(|existing| existing.application == install.application)
+                {
+                    report.push(LocateStep::CandidateRejected {
+                        path: install.application,
+                        reason: "already found through the registry".to_owned(),
+                    });
+                } else {
+                    installs.push(install);
+                }
+            }
+        }
+
+        if installs.is_empty() {
+            Err(Error::NotInstalled)
+        } else {
+            Ok(installs)
+        }
     }
 
-    #[cfg(not(target_os = "macos"))]
-    fn locate_from_windows_directory(root: PathBuf) -> Result<RobloxStudio> {
+    fn candidates_from_vinegar(
+        data: &Path,
+        config: &Path,
+        source: &StudioSource,
+        report: &mut LocateReport,
+    ) -> Result<Vec<RobloxStudio>> {
+        let vinegar = vinegar::locate(data, config, report)?;
+        let plugins = Self::locate_plugins_on_windows(vinegar.roblox)?;
+
+        Ok(vinegar
+            .versions
+            .into_iter()
+            .map(|version| {
+                let install = Self::from_windows_version(version, plugins.clone(), source.clone());
+                report.push(LocateStep::CandidateFound(install.application.clone()));
+                install
+            })
+            .collect())
+    }
+
+    fn candidates_from_directory(
+        root: PathBuf,
+        source: StudioSource,
+        platform: Platform,
+        report: &mut LocateReport,
+    ) -> Result<Vec<RobloxStudio>> {
+        report.push(LocateStep::CandidateDirectory(root.clone()));
+
+        match platform {
+            Platform::Windows => Self::candidates_from_windows_directory(root, source, report),
+            Platform::MacOs if is_bundle(&root) => {
+                Self::from_macos_bundle(root, source, report).map(|install| vec![install])
+            }
+            Platform::MacOs => Self::candidates_from_applications_directory(root, source, report),
+        }
+    }
+
+    fn candidates_from_windows_directory(
+        root: PathBuf,
+        source: StudioSource,
+        report: &mut LocateReport,
+    ) -> Result<Vec<RobloxStudio>> {
         let content_folder_path = root.join("content");
-        
+
         if content_folder_path.is_dir() {
-            let roblox_root = root.parent().unwrap().to_path_buf().parent().unwrap().to_path_buf();
+            let roblox_root = root
+                .parent()
+                .and_then(Path::parent)
+                .ok_or_else(|| Error::RobloxDirectoryNotFound(root.clone()))?
+                .to_path_buf();
             let plugins = Self::locate_plugins_on_windows(roblox_root)?;
-            
-            Ok(RobloxStudio {
+
+            let install = RobloxStudio {
                 content: content_folder_path,
                 application: root.join("RobloxStudioBeta.exe"),
                 built_in_plugins: root.join("BuiltInPlugins"),
                 plugins,
                 root,
-            })
+                source,
+                platform: Platform::Windows,
+            };
+
+            report.push(LocateStep::CandidateFound(install.application.clone()));
+            Ok(vec![install])
         } else {
+            report.push(LocateStep::MissingFile(content_folder_path));
+
             let roblox_root = root.clone();
             let plugins = Self::locate_plugins_on_windows(roblox_root)?;
             let versions = root.join("Versions");
 
-            if versions.is_dir() {
-                fs::read_dir(&versions)
-                    .map_err(|_| Error::NotInstalled)?
-                    .filter_map(|entry| entry.ok())
-                    .find_map(|entry| {
-                        let version = entry.path();
-                        let application = version.join("RobloxStudioBeta.exe");
-
-                        if application.is_file() {
-                            Some(RobloxStudio {
-                                content: version.join("content"),
-                                application,
-                                built_in_plugins: version.join("BuiltInPlugins"),
-                                plugins: plugins.clone(),
-                                root: version.to_owned(),
-                            })
-                        } else {
-                            None
-                        }
+            // a `Versions` file is reported as an unreadable `Versions` directory
+            if versions.exists() {
+                let installs: Vec<RobloxStudio> = Self::version_directories(&versions, report)?
+                    .into_iter()
+                    .map(|version| {
+                        Self::from_windows_version(version, plugins.clone(), source.clone())
                     })
-                    .ok_or(Error::NotInstalled)
+                    .collect();
+
+                if installs.is_empty() {
+                    Err(Error::NoVersionFound(versions))
+                } else {
+                    Ok(installs)
+                }
             } else {
-                Err(Error::NotInstalled)
+                report.push(LocateStep::MissingFile(versions));
+                Err(Error::StudioDirectoryNotFound(root))
             }
         }
     }
 
-    #[cfg(target_os = "macos")]
-    fn locate_from_directory(root: PathBuf) -> Result<RobloxStudio> {
+    /// The directories inside a `Versions` directory that contain `RobloxStudioBeta.exe`.
+    fn version_directories(versions: &Path, report: &mut LocateReport) -> Result<Vec<PathBuf>> {
+        let entries = fs::read_dir(versions).map_err(|source| Error::VersionsDirectoryUnreadable {
+            path: versions.to_owned(),
+            source,
+        })?;
+
+        let mut version_directories = Vec::new();
+
+        for version in entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()) {
+            let application = version.join("RobloxStudioBeta.exe");
+
+            if application.is_file() {
+                report.push(LocateStep::CandidateFound(application));
+                version_directories.push(version);
+            } else {
+                report.push(LocateStep::MissingFile(application));
+            }
+        }
+
+        Ok(version_directories)
+    }
+
+    fn from_windows_version(
+        version: PathBuf,
+        plugins: PathBuf,
+        source: StudioSource,
+    ) -> RobloxStudio {
+        RobloxStudio {
+            content: version.join("content"),
+            application: version.join("RobloxStudioBeta.exe"),
+            built_in_plugins: version.join("BuiltInPlugins"),
+            plugins,
+            root: version,
+            source,
+            platform: Platform::Windows,
+        }
+    }
+
+    /// Searches a directory such as `/Applications` for Roblox Studio bundles. Bundles can
+    /// be renamed, so they are recognized by their identifier instead of their name.
+    fn candidates_from_applications_directory(
+        directory: PathBuf,
+        source: StudioSource,
+        report: &mut LocateReport,
+    ) -> Result<Vec<RobloxStudio>> {
+        let entries = match fs::read_dir(&directory) {
+            Ok(entries) => entries,
+            Err(_) => {
+                report.push(LocateStep::MissingFile(directory.clone()));
+                return Err(Error::NoVersionFound(directory));
+            }
+        };
+
+        let mut bundles: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| is_bundle(path))
+            .collect();
+
+        // keep the search order independent from the file system
+        bundles.sort();
+
+        let mut installs = Vec::new();
+
+        for bundle in bundles {
+            // other applications are not reported, as there can be many of them
+            if let Ok(info) = BundleInfo::read(&bundle) {
+                if info.identifier() == Some(ROBLOX_STUDIO_BUNDLE_IDENTIFIER) {
+                    installs.push(Self::from_macos_bundle(bundle, source.clone(), report)?);
+                }
+            }
+        }
+
+        if installs.is_empty() {
+            Err(Error::NoVersionFound(directory))
+        } else {
+            Ok(installs)
+        }
+    }
+
+    fn from_macos_bundle(
+        root: PathBuf,
+        source: StudioSource,
+        report: &mut LocateReport,
+    ) -> Result<RobloxStudio> {
         let contents = root.join("Contents");
-        let application = contents.join("MacOS").join("RobloxStudio");
+        let info_path = BundleInfo::path(&root);
+
+        // the executable is named by the bundle's `Info.plist`, so renamed executables are
+        // still found
+        let executable = match BundleInfo::read(&root) {
+            Ok(info) => {
+                report.push(LocateStep::FileRead(info_path));
+
+                info.executable()
+                    .and_then(|executable| Path::new(executable).file_name())
+                    .map(PathBuf::from)
+            }
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                report.push(LocateStep::MissingFile(info_path));
+                None
+            }
+            Err(source) => {
+                return Err(Error::BundleInfoUnreadable {
+                    path: info_path,
+                    source,
+                })
+            }
+        };
+
+        let application = contents
+            .join("MacOS")
+            .join(executable.unwrap_or_else(|| PathBuf::from("RobloxStudio")));
         let built_in_plugins = contents.join("Resources").join("BuiltInPlugins");
-        let documents = dirs::document_dir().ok_or(Error::DocumentsDirectoryNotFound)?;
+        let documents = dirs::document_dir()
+            .or_else(|| dirs::home_dir().map(|home| home.join("Documents")))
+            .ok_or(Error::DocumentsDirectoryNotFound)?;
         let plugins = documents.join("Roblox").join("Plugins");
         let content = contents.join("Resources").join("content");
 
+        if application.is_file() {
+            report.push(LocateStep::CandidateFound(application.clone()));
+        } else {
+            report.push(LocateStep::MissingFile(application.clone()));
+        }
+
         Ok(RobloxStudio {
             content,
             application,
             built_in_plugins,
             plugins,
             root,
+            source,
+            platform: Platform::MacOs,
         })
     }
 
-    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
-    #[inline]
-    fn locate_from_directory(root: PathBuf) -> Result<RobloxStudio> {
-        // for users running WSL, we need to find the Roblox Windows installation
-        // even if we're not on Windows
-        Self::locate_from_windows_directory(root).map_err(|_| Error::PlatformNotSupported)
-    }
-
     #[deprecated(
         since = "0.2.0",
         note = "The contents of the studio directory are inconsistent across platforms. \
@@ -234,23 +988,231 @@ impl RobloxStudio {
     #[inline]
     /// Path to the user's plugin directory. This directory may NOT exist if the Roblox Studio
     /// user has never opened it from Roblox Studio `Plugins Folder` button.
+    /// [`RobloxStudio::install_plugin`] creates it when needed.
     pub fn plugins_path(&self) -> &Path {
         &self.plugins
     }
 
-    fn locate_from_env() -> Option<Result<RobloxStudio>> {
-        let variable_value = env::var(ROBLOX_STUDIO_PATH_VARIABLE).ok()?;
+    /// Installs a plugin in [`RobloxStudio::plugins_path`] and returns its path. The directory
+    /// is created when Roblox Studio hasn't yet. `name` is the file name of the plugin, with
+    /// an extension Roblox Studio loads plugins from: `.rbxm`, `.rbxmx`, `.lua` or `.luau`.
+    ///
+    /// The plugin is written to a temporary file and then renamed, so Roblox Studio never
+    /// loads a partially written plugin.
+    ///
+    /// Fails with [`Error::PluginAlreadyInstalled`] when a plugin with this name is already
+    /// installed, use [`RobloxStudio::replace_plugin`] to overwrite it. Fails with
+    /// [`Error::InvalidPluginName`] when `name` isn't a plugin file name, or isn't an `.rbxmx`
+    /// file for a [`PluginModel`], and with [`Error::PluginInstallFailed`] when the plugin
+    /// can't be written.
+    ///
+    /// ```no_run
+    /// use roblox_install::RobloxStudio;
+    ///
+    /// let studio = RobloxStudio::locate()?;
+    /// studio.install_plugin("Hello.lua", b"print(\"Hello!\")")?;
+    /// # Ok::<(), roblox_install::Error>(())
+    /// ```
+    pub fn install_plugin<'a, S: Into<PluginSource<'a>>>(
+        &self,
+        name: &str,
+        source: S,
+    ) -> Result<PathBuf> {
+        plugins::install(&self.plugins, name, source.into(), false)
+    }
 
-        let result = variable_value
-            .parse()
-            .map_err(|error| {
-                Error::EnvironmentVariableError(format!(
-                    "could not convert environment variable `{}` to path ({})",
-                    ROBLOX_STUDIO_PATH_VARIABLE, error,
-                ))
-            })
-            .and_then(Self::locate_from_directory);
+    /// Same as [`RobloxStudio::install_plugin`], but overwrites the plugin when it is already
+    /// installed.
+    pub fn replace_plugin<'a, S: Into<PluginSource<'a>>>(
+        &self,
+        name: &str,
+        source: S,
+    ) -> Result<PathBuf> {
+        plugins::install(&self.plugins, name, source.into(), true)
+    }
+
+    /// Removes a plugin, given by its file name, from [`RobloxStudio::plugins_path`].
+    ///
+    /// Fails with [`Error::PluginNotInstalled`] when there is no such plugin, and with
+    /// [`Error::PluginRemovalFailed`] when it can't be removed.
+    pub fn uninstall_plugin(&self, name: &str) -> Result<()> {
+        plugins::uninstall(&self.plugins, name)
+    }
+
+    /// Lists the plugins in [`RobloxStudio::plugins_path`], sorted by name, with their kind,
+    /// size, modification time and checksum. A missing directory has no plugins.
+    ///
+    /// The kind of each plugin is detected from its contents, and malformed plugins, such as
+    /// empty or truncated models, are flagged with [`PluginIssue`]s, so a broken plugin can be
+    /// diagnosed without opening Roblox Studio. This reads every plugin.
+    ///
+    /// Fails with [`Error::PluginsDirectoryUnreadable`] when the directory can't be read.
+    pub fn list_plugins(&self) -> Result<Vec<Plugin>> {
+        plugins::list(&self.plugins)
+    }
+
+    /// Installs the plugins a `roblox-plugins.toml` manifest declares (see
+    /// [`PluginManifest`]) in [`RobloxStudio::plugins_path`], updating the ones whose source
+    /// changed. The checksums of the installed plugins are recorded in a lockfile next to the
+    /// manifest, `roblox-plugins.lock`, to remove plugins that are no longer declared.
+    ///
+    /// Running it again without changes is a no-op, and running it again after it was
+    /// interrupted finishes the sync. Plugins this manifest didn't install are never
+    /// overwritten nor removed: an undeclared plugin that was modified after being installed
+    /// is left in place and forgotten.
+    ///
+    /// Fails with [`Error::PluginConflict`] when a declared plugin is already installed but
+    /// wasn't installed from this manifest, in which case nothing is changed. Fails with
+    /// [`Error::PluginManifestUnreadable`] or [`Error::MalformedPluginManifest`] when the
+    /// manifest or lockfile can't be used, with [`Error::PluginSourceUnreadable`] when a
+    /// declared plugin can't be read, with [`Error::InvalidPluginName`] when a name isn't a
+    /// plugin file name, and with [`Error::PluginInstallFailed`],
+    /// [`Error::PluginRemovalFailed`] or [`Error::PluginLockfileUnwritable`] when a file can't
+    /// be written.
+    pub fn sync_plugins<P: AsRef<Path>>(&self, manifest: P) -> Result<PluginSyncReport> {
+        plugin_sync::sync(&self.plugins, manifest.as_ref())
+    }
+
+    /// Creates a [`PluginWatcher`] deploying `source` as the plugin `name` in
+    /// [`RobloxStudio::plugins_path`] whenever it changes. A file is deployed as a plugin file
+    /// and a directory as a plugin folder.
+    ///
+    /// Fails with [`Error::InvalidPluginName`] when `name` can't be used, such as a file source
+    /// deployed under a name Roblox Studio doesn't load plugins from.
+    pub fn watch_plugin<P: AsRef<Path>>(&self, name: &str, source: P) -> Result<PluginWatcher> {
+        PluginWatcher::new(&self.plugins, name, source.as_ref())
+    }
+
+    #[must_use]
+    #[inline]
+    /// Where this installation was found.
+    pub fn source(&self) -> &StudioSource {
+        &self.source
+    }
+
+    #[must_use]
+    #[inline]
+    /// The layout of this installation.
+    pub fn platform(&self) -> Platform {
+        self.platform
+    }
+
+    /// Reads the version of this installation. The hash comes from the name of the version
+    /// directory (`version-<hash>`), which only exists with the [`Platform::Windows`] layout.
+    /// The dotted version comes from the version resource of `RobloxStudioBeta.exe` on
+    /// Windows, and from the `Info.plist` of the bundle on macOS.
+    ///
+    /// When the hash is known, a dotted version that can't be read is left out. Otherwise, fails
+    /// with [`Error::VersionUnreadable`] when the executable or `Info.plist` can't be read,
+    /// with [`Error::CorruptExecutable`] when the executable is truncated or malformed, and
+    /// with [`Error::VersionNotFound`] when neither the hash nor the dotted version are known.
+    pub fn version(&self) -> Result<StudioVersion> {
+        StudioVersion::read(&self.root, &self.application, self.platform)
+    }
+
+    /// Reads the version resource of `RobloxStudioBeta.exe`, which holds its file and product
+    /// versions and company name. See [`VersionResource::read`] for the errors.
+    ///
+    /// Only installations with the [`Platform::Windows`] layout have one, others fail with
+    /// [`Error::PlatformNotSupported`].
+    pub fn version_resource(&self) -> Result<VersionResource> {
+        match self.platform {
+            Platform::Windows => VersionResource::read(&self.application),
+            Platform::MacOs => Err(Error::PlatformNotSupported),
+        }
+    }
+
+    /// Checks this installation against the `rbxPkgManifest.txt` of its version directory,
+    /// to find installations left incomplete by an interrupted update. Locating Roblox Studio
+    /// doesn't check this, as it means reading the whole version directory.
+    ///
+    /// The files extracted from each package must add up to the size the manifest lists, and
+    /// package zips left in the directory must match their checksum. Directories next to the
+    /// packages' that no package extracts to are reported too.
+    ///
+    /// Fails with [`Error::PackageManifestUnreadable`] or [`Error::MalformedPackageManifest`]
+    /// when the manifest can't be used. Only installations with the [`Platform::Windows`]
+    /// layout have one, others fail with [`Error::PlatformNotSupported`].
+    pub fn verify(&self) -> Result<IntegrityReport> {
+        match self.platform {
+            Platform::Windows => verify::verify(&self.root, &self.application),
+            Platform::MacOs => Err(Error::PlatformNotSupported),
+        }
+    }
+
+    /// Removes the old version directories Roblox Studio updates leave in `Versions`, keeping
+    /// the `keep` most relevant ones, and returns the size of every directory kept and
+    /// removed.
+    ///
+    /// This installation's version is always kept, as is the version the `ContentFolder`
+    /// registry value points at, when there is one. The others are kept in order of preference:
+    /// complete versions, with both `RobloxStudioBeta.exe` and `rbxPkgManifest.txt`, before
+    /// incomplete ones, then from the most recently modified executable. Versions of the Roblox
+    /// Player, which shares the `Versions` directory, are never touched: only directories
+    /// holding `RobloxStudioBeta.exe` or `rbxPkgManifest.txt` are Roblox Studio versions.
+    ///
+    /// Fails with [`Error::NotInVersionsDirectory`] when this installation isn't in a
+    /// `Versions` directory, and with [`Error::VersionRemovalFailed`] when a directory can't be
+    /// removed. Only installations with the [`Platform::Windows`] layout have versions, others
+    /// fail with [`Error::PlatformNotSupported`].
+    pub fn prune_versions(&self, keep: usize) -> Result<PruneReport> {
+        self.prune(keep, false)
+    }
+
+    /// Same as [`RobloxStudio::prune_versions`], but only reports what would be removed.
+    pub fn prune_versions_dry_run(&self, keep: usize) -> Result<PruneReport> {
+        self.prune(keep, true)
+    }
+
+    fn prune(&self, keep: usize, dry_run: bool) -> Result<PruneReport> {
+        match self.platform {
+            Platform::Windows => {
+                let registry_content = Self::registry_content_folder_for(&self.source);
+                prune::prune(&self.root, registry_content, keep, dry_run)
+            }
+            Platform::MacOs => Err(Error::PlatformNotSupported),
+        }
+    }
+
+    /// Measures the bytes used by this installation, its user plugins, the other versions of
+    /// its `Versions` directory, and the logs and caches of Roblox, to decide what can be
+    /// cleaned up. The directories are found with the same rules as the rest of this
+    /// installation, so Wine prefixes are measured too. Directories that can't be read count
+    /// as empty.
+    ///
+    /// This reads every file of the installation, so it can take a while.
+    pub fn disk_usage(&self) -> DiskUsage {
+        usage::disk_usage(self)
+    }
+
+    fn application_modified(&self) -> Option<SystemTime> {
+        fs::metadata(&self.application)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    }
+
+    fn env_root(report: &mut LocateReport) -> Option<Result<PathBuf>> {
+        let variable_value = env::var(ROBLOX_STUDIO_PATH_VARIABLE).ok();
+
+        report.push(LocateStep::EnvironmentVariable {
+            name: ROBLOX_STUDIO_PATH_VARIABLE.to_owned(),
+            value: variable_value.clone(),
+        });
+
+        let variable_value = variable_value?;
+
+        let result = variable_value.parse().map_err(|error| {
+            Error::EnvironmentVariableError(format!(
+                "could not convert environment variable `{}` to path ({})",
+                ROBLOX_STUDIO_PATH_VARIABLE, error,
+            ))
+        });
 
         Some(result)
     }
 }
+
+/// Whether a path is a macOS application bundle rather than a directory containing them.
+fn is_bundle(path: &Path) -> bool {
+    path.extension() == Some("app".as_ref()) || path.join("Contents").is_dir()
+}
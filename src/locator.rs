@@ -0,0 +1,230 @@
+use std::path::PathBuf;
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+use crate::{vinegar, wine};
+use crate::{
+    Error, LocateReport, LocateStep, Platform, Result, RobloxStudio, StudioSource, VersionSelection,
+};
+
+/// Finds Roblox Studio installations by searching a list of [`StudioSource`]s in order.
+///
+/// [`StudioLocator::new`] searches the same sources as [`RobloxStudio::locate`]. Sources
+/// can be added, reordered or removed, which also makes it possible to search a specific
+/// directory without setting the `ROBLOX_STUDIO_PATH` environment variable:
+///
+/// ```no_run
+/// use roblox_install::StudioLocator;
+///
+/// let studio = StudioLocator::empty()
+///     .directory("/mnt/c/Users/roblox/AppData/Local/Roblox")
+///     .locate()?;
+/// # Ok::<(), roblox_install::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+#[must_use]
+pub struct StudioLocator {
+    sources: Vec<StudioSource>,
+    version_selection: VersionSelection,
+    platform: Platform,
+}
+
+impl Default for StudioLocator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StudioLocator {
+    /// Creates a locator searching the `ROBLOX_STUDIO_PATH` environment variable, then the
+    /// registry, then the platform's default installation directory. On Linux, the
+    /// [Vinegar](https://vinegarhq.org) installations of the user are searched next, and the
+    /// default Wine prefix (`$WINEPREFIX` or `~/.wine`) is searched last.
+    pub fn new() -> Self {
+        let locator = Self::empty().sources(vec![
+            StudioSource::EnvironmentVariable,
+            StudioSource::Registry,
+            StudioSource::DefaultLocation,
+        ]);
+
+        #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+        let locator = vinegar::default_directories()
+            .into_iter()
+            .fold(locator, |locator, (data, config)| {
+                locator.vinegar(data, config)
+            });
+
+        #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+        let locator = match wine::default_prefix() {
+            Some(prefix) => locator.wine_prefix(prefix),
+            None => locator,
+        };
+
+        locator
+    }
+
+    /// Creates a locator without any source.
+    pub fn empty() -> Self {
+        StudioLocator {
+            sources: Vec::new(),
+            version_selection: VersionSelection::default(),
+            platform: Platform::current(),
+        }
+    }
+
+    /// Adds a source to search after the current ones. A source that was already added is
+    /// moved to the end instead.
+    pub fn source(mut self, source: StudioSource) -> Self {
+        self.sources.retain(|existing| *existing != source);
+        self.sources.push(source);
+        self
+    }
+
+    /// Replaces every source with the given ones, searched in the given order.
+    pub fn sources<I: IntoIterator<Item = StudioSource>>(mut self, sources: I) -> Self {
+        self.sources.clear();
+        sources.into_iter().fold(self, Self::source)
+    }
+
+    /// Stops searching the given source.
+    pub fn without_source(mut self, source: &StudioSource) -> Self {
+        self.sources.retain(|existing| existing != source);
+        self
+    }
+
+    /// Adds a [`StudioSource::Directory`] source for the given directory.
+    pub fn directory<P: Into<PathBuf>>(self, root: P) -> Self {
+        self.source(StudioSource::Directory(root.into()))
+    }
+
+    /// Adds a [`StudioSource::WinePrefix`] source for the given Wine prefix.
+    pub fn wine_prefix<P: Into<PathBuf>>(self, prefix: P) -> Self {
+        self.source(StudioSource::WinePrefix(prefix.into()))
+    }
+
+    /// Adds a [`StudioSource::Vinegar`] source for the Vinegar installation with the given
+    /// data directory and configuration file.
+    pub fn vinegar<D: Into<PathBuf>, C: Into<PathBuf>>(self, data: D, config: C) -> Self {
+        self.source(StudioSource::Vinegar {
+            data: data.into(),
+            config: config.into(),
+        })
+    }
+
+    /// Sets how a version is picked when a directory contains several of them.
+    pub fn version_selection(mut self, version_selection: VersionSelection) -> Self {
+        self.version_selection = version_selection;
+        self
+    }
+
+    /// Sets the layout rules used for the `ROBLOX_STUDIO_PATH` environment variable and
+    /// explicit directories. Defaults to [`Platform::current`].
+    pub fn platform(mut self, platform: Platform) -> Self {
+        self.platform = platform;
+        self
+    }
+
+    /// The sources that will be searched, in order.
+    #[must_use]
+    #[inline]
+    pub fn search_sources(&self) -> &[StudioSource] {
+        &self.sources
+    }
+
+    /// Returns the first installation found by searching each source in order.
+    ///
+    /// The environment variable and explicit directories are set on purpose, so when one of
+    /// them is defined but does not lead to an installation, its error is returned right
+    /// away. Errors from other sources only mean Studio wasn't found there, and the next
+    /// source is searched. If no source finds an installation, the first error encountered
+    /// is returned.
+    pub fn locate(&self) -> Result<RobloxStudio> {
+        self.locate_with_report().0
+    }
+
+    /// Same as [`locate`](StudioLocator::locate), but also returns a [`LocateReport`] of
+    /// every source searched and every path checked along the way, which helps understanding
+    /// why an installation was picked or why none was found.
+    ///
+    /// ```no_run
+    /// use roblox_install::StudioLocator;
+    ///
+    /// let (studio, report) = StudioLocator::new().locate_with_report();
+    ///
+    /// if studio.is_err() {
+    ///     eprint!("{}", report);
+    /// }
+    /// ```
+    pub fn locate_with_report(&self) -> (Result<RobloxStudio>, LocateReport) {
+        let mut report = LocateReport::default();
+        let mut first_error = None;
+
+        for source in &self.sources {
+            report.push(LocateStep::SearchSource(source.clone()));
+
+            match RobloxStudio::candidates_from_source(source, self.platform, &mut report) {
+                Some(Ok(mut installs)) => {
+                    self.version_selection.sort(&mut installs);
+
+                    if let Some(install) = installs.into_iter().next() {
+                        report.push(LocateStep::Selected(install.application.clone()));
+                        return (Ok(install), report);
+                    }
+                }
+
+                Some(Err(error)) => {
+                    report.push(LocateStep::SourceFailed {
+                        source: source.clone(),
+                        error: error.to_string(),
+                    });
+
+                    match source {
+                        StudioSource::EnvironmentVariable | StudioSource::Directory(_) => {
+                            return (Err(error), report)
+                        }
+                        _ => {
+                            first_error.get_or_insert(error);
+                        }
+                    }
+                }
+
+                None => {}
+            }
+        }
+
+        (Err(first_error.unwrap_or(Error::NotInstalled)), report)
+    }
+
+    /// Returns every installation found in every source. Installations are ordered by
+    /// source, then by the [`VersionSelection`] within a source. Only installations whose
+    /// executable exists are kept.
+    ///
+    /// Each installation is tagged with the [`StudioSource`] it was found through. An
+    /// installation reachable from several sources is only returned once, tagged with the
+    /// first source that found it.
+    #[must_use]
+    pub fn locate_all(&self) -> Vec<RobloxStudio> {
+        let mut installs_found: Vec<RobloxStudio> = Vec::new();
+        let mut report = LocateReport::default();
+
+        for source in &self.sources {
+            let candidates =
+                RobloxStudio::candidates_from_source(source, self.platform, &mut report);
+
+            if let Some(Ok(mut installs)) = candidates {
+                self.version_selection.sort(&mut installs);
+
+                for install in installs {
+                    if install.application.is_file()
+                        && !installs_found
+                            .iter()
+                            .any(|found| found.application == install.application)
+                    {
+                        installs_found.push(install);
+                    }
+                }
+            }
+        }
+
+        installs_found
+    }
+}
@@ -0,0 +1,192 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{Error, Result};
+
+/// The name of the package manifest in a version directory.
+pub(crate) const MANIFEST_FILE_NAME: &str = "rbxPkgManifest.txt";
+
+/// Where the packages of Roblox Studio are extracted, relative to the version directory. An
+/// empty destination is the version directory itself.
+const PACKAGE_DESTINATIONS: &[(&str, &str)] = &[
+    ("ApplicationConfig.zip", "ApplicationConfig"),
+    ("BuiltInPlugins.zip", "BuiltInPlugins"),
+    ("BuiltInStandalonePlugins.zip", "BuiltInStandalonePlugins"),
+    ("content-api-docs.zip", "content/api_docs"),
+    ("content-avatar.zip", "content/avatar"),
+    ("content-configs.zip", "content/configs"),
+    ("content-fonts.zip", "content/fonts"),
+    ("content-models.zip", "content/models"),
+    (
+        "content-platform-dictionaries.zip",
+        "PlatformContent/pc/shared_compression_dictionaries",
+    ),
+    ("content-platform-fonts.zip", "PlatformContent/pc/fonts"),
+    ("content-qt_translations.zip", "content/qt_translations"),
+    ("content-sky.zip", "content/sky"),
+    ("content-sounds.zip", "content/sounds"),
+    (
+        "content-studio_svg_textures.zip",
+        "content/studio_svg_textures",
+    ),
+    ("content-terrain.zip", "PlatformContent/pc/terrain"),
+    ("content-textures2.zip", "content/textures"),
+    ("content-textures3.zip", "PlatformContent/pc/textures"),
+    ("extracontent-luapackages.zip", "ExtraContent/LuaPackages"),
+    ("extracontent-models.zip", "ExtraContent/models"),
+    ("extracontent-scripts.zip", "ExtraContent/scripts"),
+    ("extracontent-textures.zip", "ExtraContent/textures"),
+    ("extracontent-translations.zip", "ExtraContent/translations"),
+    ("LibrariesQt5.zip", ""),
+    ("Plugins.zip", "Plugins"),
+    ("Qml.zip", "Qml"),
+    ("redist.zip", ""),
+    ("RibbonConfig.zip", "RibbonConfig"),
+    ("RobloxStudio.zip", ""),
+    ("shaders.zip", "shaders"),
+    ("ssl.zip", "ssl"),
+    ("studiocontent-models.zip", "StudioContent/models"),
+    ("studiocontent-textures.zip", "StudioContent/textures"),
+    ("StudioFonts.zip", "StudioFonts"),
+    ("WebView2.zip", ""),
+    ("WebView2RuntimeInstaller.zip", "WebView2RuntimeInstaller"),
+];
+
+/// The `rbxPkgManifest.txt` file of a Roblox Studio version, listing the packages the
+/// version is made of. It starts with a `v0` line, followed by four lines per package: its
+/// name, the MD5 checksum of its zip, the size of its zip and the size of its contents.
+///
+/// ```text
+/// v0
+/// BuiltInPlugins.zip
+/// 0123456789abcdef0123456789abcdef
+/// 1024
+/// 4096
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PackageManifest {
+    packages: Vec<Package>,
+}
+
+/// A package of a [`PackageManifest`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Package {
+    name: String,
+    checksum: String,
+    packed_size: u64,
+    size: u64,
+}
+
+impl PackageManifest {
+    /// Reads a `rbxPkgManifest.txt` file.
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<PackageManifest> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path).map_err(|source| Error::PackageManifestUnreadable {
+            path: path.to_owned(),
+            source,
+        })?;
+
+        parse_manifest(&text).map_err(|reason| {
+            Error::MalformedPackageManifest(format!("{} in `{}`", reason, path.display()))
+        })
+    }
+
+    /// Parses the contents of a `rbxPkgManifest.txt` file.
+    pub fn parse(text: &str) -> Result<PackageManifest> {
+        parse_manifest(text).map_err(Error::MalformedPackageManifest)
+    }
+
+    /// The packages, in the order of the manifest.
+    #[must_use]
+    #[inline]
+    pub fn packages(&self) -> &[Package] {
+        &self.packages
+    }
+}
+
+fn parse_manifest(text: &str) -> std::result::Result<PackageManifest, String> {
+    let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+
+    match lines.next() {
+        Some("v0") => {}
+        Some(version) => return Err(format!("unsupported version `{}`", version)),
+        None => return Err("missing version".to_owned()),
+    }
+
+    let lines: Vec<&str> = lines.collect();
+    let chunks = lines.chunks_exact(4);
+
+    if let Some(incomplete) = chunks.remainder().first() {
+        return Err(format!("incomplete package `{}`", incomplete));
+    }
+
+    let packages = chunks
+        .map(|package| {
+            let name = package[0];
+            let checksum = package[1].to_ascii_lowercase();
+
+            if checksum.len() != 32 || !checksum.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(format!("invalid checksum for `{}`", name));
+            }
+
+            let size = |line: &str| {
+                line.parse()
+                    .map_err(|_| format!("invalid size `{}` for `{}`", line, name))
+            };
+
+            Ok(Package {
+                name: name.to_owned(),
+                checksum,
+                packed_size: size(package[2])?,
+                size: size(package[3])?,
+            })
+        })
+        .collect::<std::result::Result<_, _>>()?;
+
+    Ok(PackageManifest { packages })
+}
+
+impl Package {
+    /// The file name of the package's zip, such as `BuiltInPlugins.zip`.
+    #[must_use]
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The MD5 checksum of the package's zip, in lowercase hexadecimal.
+    #[must_use]
+    #[inline]
+    pub fn checksum(&self) -> &str {
+        &self.checksum
+    }
+
+    /// The size of the package's zip, in bytes.
+    #[must_use]
+    #[inline]
+    pub fn packed_size(&self) -> u64 {
+        self.packed_size
+    }
+
+    /// The size of the files in the package once extracted, in bytes.
+    #[must_use]
+    #[inline]
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Where the package is extracted, relative to the version directory, such as
+    /// `content/fonts`. An empty path is the version directory itself. Returns `None` for
+    /// packages this crate doesn't know.
+    #[must_use]
+    pub fn destination(&self) -> Option<PathBuf> {
+        PACKAGE_DESTINATIONS
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(&self.name))
+            .map(|(_, destination)| destination.split('/').collect())
+    }
+}
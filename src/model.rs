@@ -0,0 +1,365 @@
+use std::{
+    fmt,
+    fs::{self, FileType},
+    io,
+    path::{Path, PathBuf},
+};
+
+use crate::{Error, Result};
+
+/// The extensions of Luau scripts.
+const SCRIPT_EXTENSIONS: &[&str] = &["lua", "luau"];
+
+/// The attributes of the `roblox` element, as written by Roblox Studio.
+const ROBLOX_ATTRIBUTES: &str = "xmlns:xmime=\"http://www.w3.org/2005/05/xmlmime\" \
+    xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" \
+    xsi:noNamespaceSchemaLocation=\"http://www.roblox.com/roblox.xsd\" version=\"4\"";
+
+/// A plugin model built from Luau scripts, written as an `.rbxmx` document. It can be given
+/// to [`RobloxStudio::install_plugin`](crate::RobloxStudio::install_plugin) as is.
+///
+/// Scripts are turned into instances the way [Rojo](https://rojo.space) does:
+///
+/// * `Name.server.lua` is a `Script` named `Name`, `Name.client.lua` is a `LocalScript` and
+///   any other `Name.lua` is a `ModuleScript`. A single file given to [`PluginModel::read`] is
+///   always a `Script`, so the plugin runs.
+/// * A directory is a `Folder` holding its scripts and subdirectories, unless it has an
+///   `init.server.lua`, `init.client.lua` or `init.lua` script, in which case it is that
+///   `Script`, `LocalScript` or `ModuleScript` instead, in that order of preference.
+/// * `.luau` works everywhere `.lua` does. Other files and hidden files are ignored, as are
+///   symbolic links to directories, which could otherwise make a directory contain itself.
+///
+/// The document is deterministic: children are sorted by file name, referents are numbered
+/// in document order and line endings are kept as written, so rebuilding an unchanged plugin
+/// gives the same bytes.
+///
+/// ```no_run
+/// use roblox_install::{PluginModel, RobloxStudio};
+///
+/// let model = PluginModel::read("src/MyPlugin")?;
+/// RobloxStudio::locate()?.replace_plugin("MyPlugin.rbxmx", &model)?;
+/// # Ok::<(), roblox_install::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PluginModel {
+    root: ModelInstance,
+}
+
+impl PluginModel {
+    /// Builds a model from a Luau script or a directory of scripts.
+    ///
+    /// Fails with [`Error::NotAScript`] when a file isn't a `.lua` or `.luau` script, and with
+    /// [`Error::PluginSourceUnreadable`] when a script or directory can't be read, including
+    /// scripts and file names that aren't valid UTF-8.
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<PluginModel> {
+        let path = path.as_ref();
+
+        let root = if path.is_dir() {
+            read_directory(path)?
+        } else {
+            let (name, _) = script_name(path).ok_or_else(|| Error::NotAScript(path.to_owned()))?;
+            ModelInstance {
+                class: InstanceClass::Script,
+                name,
+                source: Some(read_script(path)?),
+                children: Vec::new(),
+            }
+        };
+
+        Ok(PluginModel { root })
+    }
+
+    /// Builds a model holding a single `Script`.
+    pub fn script<N: Into<String>, S: Into<String>>(name: N, source: S) -> PluginModel {
+        PluginModel {
+            root: ModelInstance {
+                class: InstanceClass::Script,
+                name: name.into(),
+                source: Some(source.into()),
+                children: Vec::new(),
+            },
+        }
+    }
+
+    /// The instance at the root of the model.
+    #[must_use]
+    #[inline]
+    pub fn root(&self) -> &ModelInstance {
+        &self.root
+    }
+
+    /// Writes the model as an `.rbxmx` document.
+    #[must_use]
+    pub fn to_rbxmx(&self) -> String {
+        let mut document = format!("<roblox {}>\n", ROBLOX_ATTRIBUTES);
+        document.push_str("\t<External>null</External>\n\t<External>nil</External>\n");
+
+        let mut referent = 0;
+        write_instance(&mut document, &self.root, 1, &mut referent);
+
+        document.push_str("</roblox>\n");
+        document
+    }
+}
+
+/// An instance of a [`PluginModel`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ModelInstance {
+    class: InstanceClass,
+    name: String,
+    source: Option<String>,
+    children: Vec<ModelInstance>,
+}
+
+impl ModelInstance {
+    /// The class of the instance.
+    #[must_use]
+    #[inline]
+    pub fn class(&self) -> InstanceClass {
+        self.class
+    }
+
+    /// The name of the instance, from the file or directory it was read from.
+    #[must_use]
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The source of scripts, or `None` for folders.
+    #[must_use]
+    #[inline]
+    pub fn source(&self) -> Option<&str> {
+        self.source.as_deref()
+    }
+
+    /// The children of the instance, sorted by the file name they were read from.
+    #[must_use]
+    #[inline]
+    pub fn children(&self) -> &[ModelInstance] {
+        &self.children
+    }
+}
+
+/// The class of a [`ModelInstance`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum InstanceClass {
+    /// A script running on its own, such as the entry point of a plugin.
+    Script,
+
+    /// A script running on its own on the client, from a `.client.lua` file.
+    LocalScript,
+
+    /// A script returning a value to the scripts requiring it.
+    ModuleScript,
+
+    /// A container for other instances.
+    Folder,
+}
+
+impl InstanceClass {
+    /// The name of the class in Roblox.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            InstanceClass::Script => "Script",
+            InstanceClass::LocalScript => "LocalScript",
+            InstanceClass::ModuleScript => "ModuleScript",
+            InstanceClass::Folder => "Folder",
+        }
+    }
+}
+
+impl fmt::Display for InstanceClass {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.as_str())
+    }
+}
+
+/// The instance name and class of a script, or `None` when the file isn't a script.
+fn script_name(path: &Path) -> Option<(String, InstanceClass)> {
+    let file_name = path.file_name()?.to_str()?;
+    let (stem, extension) = file_name.rsplit_once('.')?;
+
+    if stem.is_empty()
+        || !SCRIPT_EXTENSIONS
+            .iter()
+            .any(|script_extension| extension.eq_ignore_ascii_case(script_extension))
+    {
+        return None;
+    }
+
+    let suffixes = [
+        (".server", InstanceClass::Script),
+        (".client", InstanceClass::LocalScript),
+    ];
+
+    for (suffix, class) in suffixes {
+        match stem.strip_suffix(suffix) {
+            Some(name) if !name.is_empty() => return Some((name.to_owned(), class)),
+            _ => {}
+        }
+    }
+
+    Some((stem.to_owned(), InstanceClass::ModuleScript))
+}
+
+fn read_script(path: &Path) -> Result<String> {
+    let source = fs::read_to_string(path).map_err(|source| Error::PluginSourceUnreadable {
+        path: path.to_owned(),
+        source,
+    })?;
+
+    Ok(match source.strip_prefix('\u{feff}') {
+        Some(source) => source.to_owned(),
+        None => source,
+    })
+}
+
+fn read_directory(directory: &Path) -> Result<ModelInstance> {
+    let unreadable = |source| Error::PluginSourceUnreadable {
+        path: directory.to_owned(),
+        source,
+    };
+
+    let mut entries: Vec<(String, PathBuf, FileType)> = Vec::new();
+
+    for entry in fs::read_dir(directory).map_err(unreadable)? {
+        let entry = entry.map_err(unreadable)?;
+        let path = entry.path();
+        let file_name = entry.file_name();
+
+        if file_name.to_string_lossy().starts_with('.') {
+            continue;
+        }
+
+        let file_name = match file_name.into_string() {
+            Ok(file_name) => file_name,
+            Err(_) => {
+                return Err(Error::PluginSourceUnreadable {
+                    path,
+                    source: io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "the file name isn't valid UTF-8",
+                    ),
+                })
+            }
+        };
+
+        let file_type = entry
+            .file_type()
+            .map_err(|source| Error::PluginSourceUnreadable {
+                path: path.clone(),
+                source,
+            })?;
+
+        entries.push((file_name, path, file_type));
+    }
+
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut class = InstanceClass::Folder;
+    let mut source = None;
+    let mut children = Vec::new();
+
+    for (_, path, file_type) in entries {
+        if file_type.is_dir() {
+            children.push(read_directory(&path)?);
+            continue;
+        }
+
+        // links to files are read through, but not links to directories
+        if file_type.is_symlink() && path.is_dir() {
+            continue;
+        }
+
+        let (name, script_class) = match script_name(&path) {
+            Some(script) => script,
+            None => continue,
+        };
+
+        if name == "init" {
+            if source.is_none() || init_preference(script_class) > init_preference(class) {
+                class = script_class;
+                source = Some(read_script(&path)?);
+            }
+
+            continue;
+        }
+
+        children.push(ModelInstance {
+            class: script_class,
+            name,
+            source: Some(read_script(&path)?),
+            children: Vec::new(),
+        });
+    }
+
+    Ok(ModelInstance {
+        class,
+        name: directory
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        source,
+        children,
+    })
+}
+
+/// Which `init` script a directory becomes when it has several of them.
+fn init_preference(class: InstanceClass) -> u8 {
+    match class {
+        InstanceClass::Script => 2,
+        InstanceClass::LocalScript => 1,
+        InstanceClass::ModuleScript | InstanceClass::Folder => 0,
+    }
+}
+
+fn write_instance(
+    document: &mut String,
+    instance: &ModelInstance,
+    depth: usize,
+    referent: &mut usize,
+) {
+    let indent = "\t".repeat(depth);
+
+    document.push_str(&format!(
+        "{}<Item class=\"{}\" referent=\"RBX{}\">\n{}\t<Properties>\n",
+        indent, instance.class, referent, indent
+    ));
+    *referent += 1;
+
+    document.push_str(&format!(
+        "{}\t\t<string name=\"Name\">{}</string>\n",
+        indent,
+        escape(&instance.name)
+    ));
+
+    if let Some(source) = &instance.source {
+        document.push_str(&format!(
+            "{}\t\t<ProtectedString name=\"Source\"><![CDATA[{}]]></ProtectedString>\n",
+            indent,
+            // `]]>` can't appear in a CDATA section, so it is split across two of them
+            source.replace("]]>", "]]]]><![CDATA[>")
+        ));
+    }
+
+    document.push_str(&format!("{}\t</Properties>\n", indent));
+
+    for child in &instance.children {
+        write_instance(document, child, depth + 1, referent);
+    }
+
+    document.push_str(&format!("{}</Item>\n", indent));
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,425 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, Read, Seek, SeekFrom},
+    path::Path,
+};
+
+use crate::{Error, Result};
+
+/// The index of the resource table in the data directories of the optional header.
+const RESOURCE_DIRECTORY: usize = 2;
+
+/// The resource type of `VS_VERSIONINFO` resources.
+const RT_VERSION: u32 = 16;
+
+const FIXED_FILE_INFO_SIGNATURE: u32 = 0xFEEF_04BD;
+
+/// The `VS_VERSIONINFO` resource of a Windows executable, such as `RobloxStudioBeta.exe`.
+///
+/// It is read without relying on any Windows API, so it works just as well on executables
+/// reached through Wine or a drive mounted in WSL. Only the headers and the resources are
+/// read, not the whole executable.
+///
+/// ```no_run
+/// use roblox_install::RobloxStudio;
+///
+/// let resource = RobloxStudio::locate()?.version_resource()?;
+///
+/// if let Some(version) = resource.product_version() {
+///     println!("Roblox Studio {}", version);
+/// }
+/// # Ok::<(), roblox_install::Error>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VersionResource {
+    fixed_file_version: Option<[u16; 4]>,
+    fixed_product_version: Option<[u16; 4]>,
+    strings: HashMap<String, String>,
+}
+
+impl VersionResource {
+    /// Reads the version resource of the given executable. An executable without a version
+    /// resource gives an empty one.
+    ///
+    /// Fails with [`Error::CorruptExecutable`] when the executable is truncated or isn't a
+    /// valid PE file, and with [`Error::VersionUnreadable`] when it can't be read at all.
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<VersionResource> {
+        let path = path.as_ref();
+
+        read_version_resource(path).map_err(|error| match error.kind() {
+            io::ErrorKind::InvalidData | io::ErrorKind::UnexpectedEof => Error::CorruptExecutable {
+                path: path.to_owned(),
+                reason: error.to_string(),
+            },
+            _ => Error::VersionUnreadable {
+                path: path.to_owned(),
+                source: error,
+            },
+        })
+    }
+
+    /// The `FileVersion` string, such as `0, 612, 0, 6120532`.
+    #[must_use]
+    #[inline]
+    pub fn file_version(&self) -> Option<&str> {
+        self.string("FileVersion")
+    }
+
+    /// The `ProductVersion` string.
+    #[must_use]
+    #[inline]
+    pub fn product_version(&self) -> Option<&str> {
+        self.string("ProductVersion")
+    }
+
+    /// The `CompanyName` string, `Roblox Corporation` for Roblox Studio.
+    #[must_use]
+    #[inline]
+    pub fn company_name(&self) -> Option<&str> {
+        self.string("CompanyName")
+    }
+
+    /// Any string of the resource's first string table, such as `FileDescription`.
+    #[must_use]
+    pub fn string(&self, name: &str) -> Option<&str> {
+        self.strings.get(name).map(String::as_str)
+    }
+
+    /// The file version from the binary part of the resource. Its numbers are limited to 16
+    /// bits, which the build number of Roblox Studio doesn't fit in, so prefer
+    /// [`file_version`](VersionResource::file_version).
+    #[must_use]
+    #[inline]
+    pub fn fixed_file_version(&self) -> Option<[u16; 4]> {
+        self.fixed_file_version
+    }
+
+    /// The product version from the binary part of the resource, limited to 16 bit numbers
+    /// like [`fixed_file_version`](VersionResource::fixed_file_version).
+    #[must_use]
+    #[inline]
+    pub fn fixed_product_version(&self) -> Option<[u16; 4]> {
+        self.fixed_product_version
+    }
+}
+
+struct Section {
+    virtual_address: u32,
+    virtual_size: u32,
+    raw_offset: u32,
+    raw_size: u32,
+}
+
+/// Reads the version resource of a PE executable. Malformed or truncated executables are
+/// reported as errors of kind [`io::ErrorKind::InvalidData`] or
+/// [`io::ErrorKind::UnexpectedEof`].
+fn read_version_resource(path: &Path) -> io::Result<VersionResource> {
+    let mut file = File::open(path)?;
+    let file_size = file.metadata()?.len();
+    let mut read_at = |offset: u64, size: usize| read_exact_at(&mut file, file_size, offset, size);
+
+    let dos_header = read_at(0, 64)?;
+
+    if !dos_header.starts_with(b"MZ") {
+        return Err(invalid("missing the `MZ` signature"));
+    }
+
+    let pe_offset = u64::from(u32_at(&dos_header, 0x3c)?);
+    let file_header = read_at(pe_offset, 24)?;
+
+    if !file_header.starts_with(b"PE\0\0") {
+        return Err(invalid("missing the `PE` signature"));
+    }
+
+    let section_count = u16_at(&file_header, 6)?;
+    let optional_header_size = u16_at(&file_header, 20)?;
+    let optional_header = read_at(pe_offset + 24, usize::from(optional_header_size))?;
+
+    // the data directories follow the fields specific to 32 or 64 bit executables
+    let directories_offset = match u16_at(&optional_header, 0)? {
+        0x10b => 96,
+        0x20b => 112,
+        _ => return Err(invalid("unknown optional header format")),
+    };
+
+    let directory_count = u32_at(&optional_header, directories_offset - 4)? as usize;
+
+    let section_table = read_at(
+        pe_offset + 24 + u64::from(optional_header_size),
+        usize::from(section_count) * 40,
+    )?;
+
+    let sections: Vec<Section> = section_table
+        .chunks_exact(40)
+        .map(|header| Section {
+            virtual_size: le_u32(&header[8..12]),
+            virtual_address: le_u32(&header[12..16]),
+            raw_size: le_u32(&header[16..20]),
+            raw_offset: le_u32(&header[20..24]),
+        })
+        .collect();
+
+    // an executable cut short still has valid headers, but its sections go past its end
+    let is_truncated = sections
+        .iter()
+        .any(|section| u64::from(section.raw_offset) + u64::from(section.raw_size) > file_size);
+
+    if is_truncated {
+        return Err(truncated());
+    }
+
+    if directory_count <= RESOURCE_DIRECTORY {
+        return Ok(VersionResource::default());
+    }
+
+    let resource_directory = directories_offset + RESOURCE_DIRECTORY * 8;
+    let resource_address = u32_at(&optional_header, resource_directory)?;
+    let resource_size = u32_at(&optional_header, resource_directory + 4)?;
+
+    if resource_address == 0 || resource_size == 0 {
+        return Ok(VersionResource::default());
+    }
+
+    let mut read_virtual = |address: u32, size: u32| {
+        let offset = file_offset(&sections, address, size)
+            .ok_or_else(|| invalid("resources outside of any section"))?;
+
+        read_at(offset, size as usize)
+    };
+
+    let resources = read_virtual(resource_address, resource_size)?;
+
+    let version_entry = match find_version_entry(&resources)? {
+        Some(entry) => entry,
+        None => return Ok(VersionResource::default()),
+    };
+
+    let version_info = read_virtual(
+        u32_at(&resources, version_entry)?,
+        u32_at(&resources, version_entry + 4)?,
+    )?;
+
+    parse_version_info(&version_info)
+}
+
+fn read_exact_at(file: &mut File, file_size: u64, offset: u64, size: usize) -> io::Result<Vec<u8>> {
+    // checking the size first keeps corrupt headers from causing huge allocations
+    if offset.saturating_add(size as u64) > file_size {
+        return Err(truncated());
+    }
+
+    let mut buffer = vec![0; size];
+    file.seek(SeekFrom::Start(offset))?;
+    file.read_exact(&mut buffer)?;
+
+    Ok(buffer)
+}
+
+/// Converts a virtual address to an offset in the file, through the section containing it.
+fn file_offset(sections: &[Section], address: u32, size: u32) -> Option<u64> {
+    sections.iter().find_map(|section| {
+        let relative = address.checked_sub(section.virtual_address)?;
+        let section_size = section.virtual_size.max(section.raw_size);
+
+        if relative >= section_size || relative.checked_add(size)? > section.raw_size {
+            return None;
+        }
+
+        Some(u64::from(section.raw_offset) + u64::from(relative))
+    })
+}
+
+/// Walks the resource directory down to the data entry of the first version resource. The
+/// directory has three levels: resource types, resource names, then languages.
+fn find_version_entry(resources: &[u8]) -> io::Result<Option<usize>> {
+    let mut offset = 0;
+
+    for level in 0..3 {
+        let named_count = usize::from(u16_at(resources, offset + 12)?);
+        let id_count = usize::from(u16_at(resources, offset + 14)?);
+        let mut target = None;
+
+        for index in 0..named_count + id_count {
+            let entry = offset + 16 + index * 8;
+            let name = u32_at(resources, entry)?;
+
+            if level > 0 || name == RT_VERSION {
+                target = Some(u32_at(resources, entry + 4)?);
+                break;
+            }
+        }
+
+        let target = match target {
+            Some(target) => target,
+            None => return Ok(None),
+        };
+
+        // the high bit tells whether the entry points at another directory or at data
+        let is_directory = target & 0x8000_0000 != 0;
+
+        if is_directory != (level < 2) {
+            return Err(invalid("malformed resource directory"));
+        }
+
+        offset = (target & 0x7fff_ffff) as usize;
+    }
+
+    Ok(Some(offset))
+}
+
+/// A node of the `VS_VERSIONINFO` tree. Every node has a key, an optional value, and
+/// children, each aligned on 32 bits.
+struct Block<'a> {
+    key: String,
+    value: &'a [u8],
+    is_text: bool,
+    after_key: &'a [u8],
+    children: &'a [u8],
+}
+
+impl<'a> Block<'a> {
+    /// Parses the block at the start of `data`, returning it and its length.
+    fn parse(data: &'a [u8]) -> io::Result<(Block<'a>, usize)> {
+        let length = usize::from(u16_at(data, 0)?);
+        let value_length = usize::from(u16_at(data, 2)?);
+        let is_text = u16_at(data, 4)? == 1;
+
+        if length < 6 || length > data.len() {
+            return Err(invalid("malformed version resource"));
+        }
+
+        let data = &data[..length];
+        let (key, key_size) = utf16_until_null(&data[6..]);
+        let value_start = align(6 + key_size).min(length);
+
+        // text values are counted in characters rather than bytes
+        let value_size = if is_text {
+            value_length * 2
+        } else {
+            value_length
+        };
+        let value_end = (value_start + value_size).min(length);
+        let children_start = align(value_end).min(length);
+
+        let block = Block {
+            key,
+            value: &data[value_start..value_end],
+            is_text,
+            after_key: &data[value_start..],
+            children: &data[children_start..],
+        };
+
+        Ok((block, length))
+    }
+
+    fn children(&self) -> io::Result<Vec<Block<'a>>> {
+        let mut children = Vec::new();
+        let mut offset = 0;
+
+        // a length of zero is padding at the end of the parent
+        while offset + 6 <= self.children.len() && u16_at(self.children, offset)? != 0 {
+            let (child, length) = Block::parse(&self.children[offset..])?;
+            children.push(child);
+            offset = align(offset + length);
+        }
+
+        Ok(children)
+    }
+
+    /// The value of a string, read up to its terminator, as some compilers count the length
+    /// of text values in bytes instead of characters.
+    fn text(&self) -> String {
+        utf16_until_null(self.after_key).0
+    }
+}
+
+fn parse_version_info(data: &[u8]) -> io::Result<VersionResource> {
+    let (root, _) = Block::parse(data)?;
+
+    if root.key != "VS_VERSION_INFO" {
+        return Err(invalid("malformed version resource"));
+    }
+
+    // the file version is followed by the product version, each split in two 32 bit halves
+    let fixed_version = |offset: usize| match root.value {
+        fixed if fixed.len() >= 24 && le_u32(&fixed[0..4]) == FIXED_FILE_INFO_SIGNATURE => {
+            let most_significant = le_u32(&fixed[offset..offset + 4]);
+            let least_significant = le_u32(&fixed[offset + 4..offset + 8]);
+
+            Some([
+                (most_significant >> 16) as u16,
+                most_significant as u16,
+                (least_significant >> 16) as u16,
+                least_significant as u16,
+            ])
+        }
+        _ => None,
+    };
+
+    let mut strings = HashMap::new();
+
+    for child in root.children()? {
+        if child.key != "StringFileInfo" {
+            continue;
+        }
+
+        if let Some(table) = child.children()?.first() {
+            for string in table.children()? {
+                if string.is_text {
+                    strings.insert(string.key.clone(), string.text());
+                }
+            }
+        }
+    }
+
+    Ok(VersionResource {
+        fixed_file_version: fixed_version(8),
+        fixed_product_version: fixed_version(16),
+        strings,
+    })
+}
+
+/// Decodes a null-terminated UTF-16 string, returning it and the number of bytes it used,
+/// terminator included.
+fn utf16_until_null(data: &[u8]) -> (String, usize) {
+    let units: Vec<u16> = data
+        .chunks_exact(2)
+        .map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+        .take_while(|unit| *unit != 0)
+        .collect();
+
+    let size = (units.len() * 2 + 2).min(data.len());
+    (String::from_utf16_lossy(&units), size)
+}
+
+fn align(offset: usize) -> usize {
+    (offset + 3) & !3
+}
+
+fn le_u32(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+fn u16_at(data: &[u8], offset: usize) -> io::Result<u16> {
+    match data.get(offset..offset + 2) {
+        Some(bytes) => Ok(u16::from_le_bytes([bytes[0], bytes[1]])),
+        None => Err(truncated()),
+    }
+}
+
+fn u32_at(data: &[u8], offset: usize) -> io::Result<u32> {
+    match data.get(offset..offset + 4) {
+        Some(bytes) => Ok(le_u32(bytes)),
+        None => Err(truncated()),
+    }
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_owned())
+}
+
+fn truncated() -> io::Error {
+    io::Error::new(io::ErrorKind::UnexpectedEof, "the executable is truncated")
+}
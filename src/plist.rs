@@ -0,0 +1,471 @@
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// How deep arrays and dictionaries of XML property lists can be nested.
+const MAX_DEPTH: usize = 64;
+
+/// The `Contents/Info.plist` file of a macOS application bundle, which names the bundle's
+/// executable and holds its identifier and version. Both the XML and the binary property
+/// list formats are supported.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BundleInfo {
+    executable: Option<String>,
+    identifier: Option<String>,
+    short_version: Option<String>,
+    version: Option<String>,
+}
+
+impl BundleInfo {
+    /// Reads the `Contents/Info.plist` file of the given bundle.
+    pub fn read<P: AsRef<Path>>(bundle: P) -> io::Result<BundleInfo> {
+        Self::parse(&fs::read(Self::path(bundle.as_ref()))?)
+    }
+
+    /// Parses the contents of an `Info.plist` file, in the XML or the binary format.
+    pub fn parse(contents: &[u8]) -> io::Result<BundleInfo> {
+        let plist = if contents.starts_with(b"bplist00") {
+            parse_binary(contents)?
+        } else {
+            let text = std::str::from_utf8(contents)
+                .map_err(|_| invalid("the property list is not valid UTF-8"))?;
+
+            parse_xml(text)?
+        };
+
+        let mut dictionary = match plist {
+            Value::Dictionary(dictionary) => dictionary,
+            _ => return Err(invalid("the property list is not a dictionary")),
+        };
+
+        let mut string = |key: &str| match dictionary.remove(key) {
+            Some(Value::String(value)) if !value.is_empty() => Some(value),
+            _ => None,
+        };
+
+        Ok(BundleInfo {
+            executable: string("CFBundleExecutable"),
+            identifier: string("CFBundleIdentifier"),
+            short_version: string("CFBundleShortVersionString"),
+            version: string("CFBundleVersion"),
+        })
+    }
+
+    pub(crate) fn path(bundle: &Path) -> PathBuf {
+        bundle.join("Contents").join("Info.plist")
+    }
+
+    /// The name of the executable in `Contents/MacOS` (`CFBundleExecutable`).
+    #[must_use]
+    #[inline]
+    pub fn executable(&self) -> Option<&str> {
+        self.executable.as_deref()
+    }
+
+    /// The bundle identifier (`CFBundleIdentifier`), `com.roblox.RobloxStudio` for Roblox
+    /// Studio.
+    #[must_use]
+    #[inline]
+    pub fn identifier(&self) -> Option<&str> {
+        self.identifier.as_deref()
+    }
+
+    /// The version displayed to users (`CFBundleShortVersionString`).
+    #[must_use]
+    #[inline]
+    pub fn short_version(&self) -> Option<&str> {
+        self.short_version.as_deref()
+    }
+
+    /// The build version (`CFBundleVersion`).
+    #[must_use]
+    #[inline]
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+}
+
+/// A value of a property list. Only strings and dictionaries are needed from bundles, other
+/// values are checked to be well formed and then discarded.
+enum Value {
+    String(String),
+    Dictionary(HashMap<String, Value>),
+    Other,
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_owned())
+}
+
+/// Parses a binary property list. It is made of objects encoded one after the other,
+/// followed by a table of their offsets and a trailer telling where to find the root object.
+fn parse_binary(data: &[u8]) -> io::Result<Value> {
+    let truncated = || invalid("the binary property list is truncated");
+
+    let trailer = data
+        .len()
+        .checked_sub(32)
+        .filter(|trailer| *trailer >= 8)
+        .map(|trailer| &data[trailer..])
+        .ok_or_else(truncated)?;
+
+    let offset_size = usize::from(trailer[6]);
+    let reference_size = usize::from(trailer[7]);
+    let object_count = be_uint(&trailer[8..16]);
+    let root = be_uint(&trailer[16..24]);
+    let offset_table = be_uint(&trailer[24..32]);
+
+    if !(1..=8).contains(&offset_size) || !(1..=8).contains(&reference_size) {
+        return Err(invalid(
+            "the binary property list has invalid integer sizes",
+        ));
+    }
+
+    let offsets = usize::try_from(offset_table)
+        .ok()
+        .zip(usize::try_from(object_count).ok())
+        .and_then(|(start, count)| {
+            let end = start.checked_add(count.checked_mul(offset_size)?)?;
+            data.get(start..end)
+        })
+        .ok_or_else(truncated)?;
+
+    let parser = BinaryParser {
+        data,
+        offsets: offsets.chunks_exact(offset_size).map(be_uint).collect(),
+        reference_size,
+    };
+
+    parser.object(root, true)
+}
+
+struct BinaryParser<'a> {
+    data: &'a [u8],
+    offsets: Vec<u64>,
+    reference_size: usize,
+}
+
+impl BinaryParser<'_> {
+    /// Decodes an object. Objects can be referenced several times, even by themselves, so
+    /// only the contents of the root dictionary are decoded, which is all bundles need.
+    fn object(&self, reference: u64, is_root: bool) -> io::Result<Value> {
+        let offset = usize::try_from(reference)
+            .ok()
+            .and_then(|reference| self.offsets.get(reference))
+            .and_then(|offset| usize::try_from(*offset).ok())
+            .ok_or_else(|| invalid("the binary property list references a missing object"))?;
+
+        let marker = *self.byte_range(offset, 1)?.first().unwrap_or(&0);
+        let info = marker & 0x0f;
+
+        match marker >> 4 {
+            // booleans and null
+            0x0 if info <= 0x09 => Ok(Value::Other),
+            // integers, reals and dates
+            0x1..=0x3 => self.byte_range(offset + 1, 1 << info).map(|_| Value::Other),
+            0x4 => {
+                let (length, start) = self.length(offset, info)?;
+                self.byte_range(start, length).map(|_| Value::Other)
+            }
+            0x5 => {
+                let (length, start) = self.length(offset, info)?;
+
+                // ASCII strings are decoded as Latin-1, which they are a subset of
+                Ok(Value::String(
+                    self.byte_range(start, length)?
+                        .iter()
+                        .map(|byte| char::from(*byte))
+                        .collect(),
+                ))
+            }
+            0x6 => {
+                let (length, start) = self.length(offset, info)?;
+                let size = length
+                    .checked_mul(2)
+                    .ok_or_else(|| invalid("invalid length in the binary property list"))?;
+                let units: Vec<u16> = self
+                    .byte_range(start, size)?
+                    .chunks_exact(2)
+                    .map(|unit| u16::from_be_bytes([unit[0], unit[1]]))
+                    .collect();
+
+                Ok(Value::String(String::from_utf16_lossy(&units)))
+            }
+            0x8 => self
+                .byte_range(offset + 1, usize::from(info) + 1)
+                .map(|_| Value::Other),
+            0xa => {
+                let (count, start) = self.length(offset, info)?;
+                self.references(start, count).map(|_| Value::Other)
+            }
+            0xd if !is_root => Ok(Value::Other),
+            0xd => {
+                let (count, start) = self.length(offset, info)?;
+                let keys = self.references(start, count)?;
+                let values_start = start + keys.len() * self.reference_size;
+                let values = self.references(values_start, count)?;
+                let mut dictionary = HashMap::with_capacity(count);
+
+                for (key, value) in keys.into_iter().zip(values) {
+                    let key = match self.object(key, false)? {
+                        Value::String(key) => key,
+                        _ => return Err(invalid("dictionary keys must be strings")),
+                    };
+
+                    dictionary.insert(key, self.object(value, false)?);
+                }
+
+                Ok(Value::Dictionary(dictionary))
+            }
+            _ => Err(invalid("unsupported binary property list object")),
+        }
+    }
+
+    /// Reads the length of an object. It is stored in the marker when it is lower than 15,
+    /// and in an integer object following the marker otherwise. Returns the length and where
+    /// the object's contents start.
+    fn length(&self, offset: usize, info: u8) -> io::Result<(usize, usize)> {
+        if info != 0x0f {
+            return Ok((usize::from(info), offset + 1));
+        }
+
+        let marker = self.byte_range(offset + 1, 1)?[0];
+
+        if marker >> 4 != 0x1 {
+            return Err(invalid("invalid length in the binary property list"));
+        }
+
+        let size = 1 << (marker & 0x0f);
+        let length = usize::try_from(be_uint(self.byte_range(offset + 2, size)?))
+            .map_err(|_| invalid("invalid length in the binary property list"))?;
+
+        Ok((length, offset + 2 + size))
+    }
+
+    fn references(&self, start: usize, count: usize) -> io::Result<Vec<u64>> {
+        let size = count
+            .checked_mul(self.reference_size)
+            .ok_or_else(|| invalid("invalid length in the binary property list"))?;
+
+        Ok(self
+            .byte_range(start, size)?
+            .chunks_exact(self.reference_size)
+            .map(be_uint)
+            .collect())
+    }
+
+    fn byte_range(&self, start: usize, length: usize) -> io::Result<&[u8]> {
+        start
+            .checked_add(length)
+            .and_then(|end| self.data.get(start..end))
+            .ok_or_else(|| invalid("the binary property list is truncated"))
+    }
+}
+
+/// Reads a big endian unsigned integer of up to 8 bytes. Bigger integers keep their lowest
+/// 8 bytes.
+fn be_uint(bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .fold(0, |value, byte| value << 8 | u64::from(*byte))
+}
+
+/// Parses an XML property list. Only the parts of XML property lists use are supported:
+/// elements, comments, processing instructions, the document type and entities.
+fn parse_xml(text: &str) -> io::Result<Value> {
+    let mut parser = XmlParser { rest: text };
+
+    loop {
+        match parser.next_tag()? {
+            Some(Tag::Open(name)) if name == "plist" => break,
+            Some(_) => continue,
+            None => return Err(invalid("missing `plist` element")),
+        }
+    }
+
+    match parser.next_tag()? {
+        Some(tag) => parser.value(tag, 0),
+        None => Err(invalid("empty `plist` element")),
+    }
+}
+
+enum Tag {
+    Open(String),
+    Close(String),
+    Empty(String),
+}
+
+struct XmlParser<'a> {
+    rest: &'a str,
+}
+
+impl XmlParser<'_> {
+    /// Skips to the next element tag, ignoring text, comments, processing instructions and
+    /// the document type.
+    fn next_tag(&mut self) -> io::Result<Option<Tag>> {
+        loop {
+            let start = match self.rest.find('<') {
+                Some(start) => start,
+                None => return Ok(None),
+            };
+
+            self.rest = &self.rest[start..];
+
+            let skip_until = if self.rest.starts_with("<!--") {
+                Some("-->")
+            } else if self.rest.starts_with("<?") {
+                Some("?>")
+            } else if self.rest.starts_with("<!") {
+                Some(">")
+            } else {
+                None
+            };
+
+            if let Some(terminator) = skip_until {
+                let end = self
+                    .rest
+                    .find(terminator)
+                    .ok_or_else(|| invalid("unterminated XML declaration or comment"))?;
+
+                self.rest = &self.rest[end + terminator.len()..];
+                continue;
+            }
+
+            let end = self
+                .rest
+                .find('>')
+                .ok_or_else(|| invalid("unterminated XML tag"))?;
+            let tag = &self.rest[1..end];
+            self.rest = &self.rest[end + 1..];
+
+            return Ok(Some(if let Some(name) = tag.strip_prefix('/') {
+                Tag::Close(name.trim().to_owned())
+            } else if let Some(tag) = tag.strip_suffix('/') {
+                Tag::Empty(element_name(tag))
+            } else {
+                Tag::Open(element_name(tag))
+            }));
+        }
+    }
+
+    /// Reads the text of an element up to its closing tag.
+    fn text(&mut self, name: &str) -> io::Result<String> {
+        let end = self
+            .rest
+            .find('<')
+            .ok_or_else(|| invalid("unterminated XML element"))?;
+        let text = unescape_xml(&self.rest[..end])?;
+        self.rest = &self.rest[end..];
+
+        match self.next_tag()? {
+            Some(Tag::Close(closed)) if closed == name => Ok(text),
+            _ => Err(invalid("unexpected XML element in a text element")),
+        }
+    }
+
+    fn value(&mut self, tag: Tag, depth: usize) -> io::Result<Value> {
+        if depth > MAX_DEPTH {
+            return Err(invalid("the property list is nested too deeply"));
+        }
+
+        let name = match tag {
+            Tag::Empty(name) => {
+                return match name.as_str() {
+                    "string" => Ok(Value::String(String::new())),
+                    "dict" => Ok(Value::Dictionary(HashMap::new())),
+                    "true" | "false" | "data" | "array" => Ok(Value::Other),
+                    _ => Err(invalid("unexpected empty XML element")),
+                }
+            }
+            Tag::Open(name) => name,
+            Tag::Close(_) => return Err(invalid("unexpected closing XML tag")),
+        };
+
+        match name.as_str() {
+            "string" => self.text(&name).map(Value::String),
+            "integer" | "real" | "date" | "data" | "true" | "false" => {
+                self.text(&name).map(|_| Value::Other)
+            }
+            "array" => {
+                loop {
+                    match self.next_tag()? {
+                        Some(Tag::Close(closed)) if closed == name => break,
+                        Some(tag) => self.value(tag, depth + 1)?,
+                        None => return Err(invalid("unterminated `array` element")),
+                    };
+                }
+
+                Ok(Value::Other)
+            }
+            "dict" => {
+                let mut dictionary = HashMap::new();
+
+                loop {
+                    match self.next_tag()? {
+                        Some(Tag::Close(closed)) if closed == name => break,
+                        Some(Tag::Open(key)) if key == "key" => {
+                            let key = self.text("key")?;
+                            let value = self
+                                .next_tag()?
+                                .ok_or_else(|| invalid("missing dictionary value"))?;
+
+                            dictionary.insert(key, self.value(value, depth + 1)?);
+                        }
+                        _ => return Err(invalid("expected a `key` element in `dict`")),
+                    }
+                }
+
+                Ok(Value::Dictionary(dictionary))
+            }
+            _ => Err(invalid("unknown property list element")),
+        }
+    }
+}
+
+/// The name of an element, without its attributes.
+fn element_name(tag: &str) -> String {
+    tag.split_whitespace().next().unwrap_or_default().to_owned()
+}
+
+fn unescape_xml(text: &str) -> io::Result<String> {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        unescaped.push_str(&rest[..start]);
+        rest = &rest[start + 1..];
+
+        let end = rest
+            .find(';')
+            .ok_or_else(|| invalid("unterminated XML entity"))?;
+        let entity = &rest[..end];
+        rest = &rest[end + 1..];
+
+        let character = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => match entity
+                .strip_prefix("#x")
+                .or_else(|| entity.strip_prefix("#X"))
+            {
+                Some(hex) => u32::from_str_radix(hex, 16).ok().and_then(char::from_u32),
+                None => entity
+                    .strip_prefix('#')
+                    .and_then(|decimal| decimal.parse().ok())
+                    .and_then(char::from_u32),
+            },
+        };
+
+        unescaped.push(character.ok_or_else(|| invalid("unknown XML entity"))?);
+    }
+
+    unescaped.push_str(rest);
+    Ok(unescaped)
+}
@@ -0,0 +1,372 @@
+use std::{
+    collections::BTreeMap,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    plugins::{self, PluginSource},
+    Error, Result,
+};
+
+const LOCKFILE_HEADER: &str =
+    "# The plugins roblox-install installed from `roblox-plugins.toml`. Don't edit it by hand.\n\n";
+
+/// A `roblox-plugins.toml` file, declaring the local plugins a project needs. Each entry of
+/// its `plugins` table maps the file name of a plugin to the file it is installed from,
+/// relative to the manifest.
+///
+/// ```toml
+/// [plugins]
+/// "MyPlugin.rbxmx" = "build/MyPlugin.rbxmx"
+/// "Hello.lua" = "plugins/Hello.lua"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PluginManifest {
+    plugins: BTreeMap<String, PathBuf>,
+}
+
+impl PluginManifest {
+    /// Reads a `roblox-plugins.toml` file. Sources are made relative to its directory.
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<PluginManifest> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path).map_err(|source| Error::PluginManifestUnreadable {
+            path: path.to_owned(),
+            source,
+        })?;
+
+        let directory = path.parent().unwrap_or_else(|| Path::new(""));
+
+        parse_manifest(&text, directory).map_err(|reason| {
+            Error::MalformedPluginManifest(format!("{} in `{}`", reason, path.display()))
+        })
+    }
+
+    /// Parses the contents of a `roblox-plugins.toml` file. Sources are kept as written.
+    pub fn parse(text: &str) -> Result<PluginManifest> {
+        parse_manifest(text, Path::new("")).map_err(Error::MalformedPluginManifest)
+    }
+
+    /// The file names of the plugins and the files they are installed from, sorted by name.
+    pub fn plugins(&self) -> impl Iterator<Item = (&str, &Path)> {
+        self.plugins
+            .iter()
+            .map(|(name, source)| (name.as_str(), source.as_path()))
+    }
+}
+
+fn parse_manifest(text: &str, directory: &Path) -> std::result::Result<PluginManifest, String> {
+    let table: toml::Table = text.parse().map_err(|error| format!("{}", error))?;
+
+    let plugins = match table.get("plugins") {
+        Some(toml::Value::Table(plugins)) => plugins,
+        Some(_) => return Err("`plugins` is not a table".to_owned()),
+        None => return Ok(PluginManifest::default()),
+    };
+
+    let plugins = plugins
+        .iter()
+        .map(|(name, source)| match source.as_str() {
+            Some(source) => Ok((name.clone(), directory.join(source))),
+            None => Err(format!("the source of `{}` is not a path", name)),
+        })
+        .collect::<std::result::Result<_, _>>()?;
+
+    Ok(PluginManifest { plugins })
+}
+
+/// The result of [`RobloxStudio::sync_plugins`](crate::RobloxStudio::sync_plugins).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PluginSyncReport {
+    changes: Vec<PluginChange>,
+}
+
+impl PluginSyncReport {
+    /// What happened to each plugin of the manifest and lockfile, sorted by name.
+    #[must_use]
+    #[inline]
+    pub fn changes(&self) -> &[PluginChange] {
+        &self.changes
+    }
+
+    /// Whether the plugins were already in sync, so nothing was changed.
+    #[must_use]
+    pub fn is_unchanged(&self) -> bool {
+        self.changes
+            .iter()
+            .all(|change| matches!(change, PluginChange::Unchanged(_)))
+    }
+}
+
+impl fmt::Display for PluginSyncReport {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for change in &self.changes {
+            writeln!(formatter, "{}", change)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// What [`RobloxStudio::sync_plugins`](crate::RobloxStudio::sync_plugins) did to a plugin,
+/// given by its file name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum PluginChange {
+    /// The plugin was not installed yet.
+    Installed(String),
+
+    /// The source of the plugin changed since it was installed.
+    Updated(String),
+
+    /// The plugin is already up to date.
+    Unchanged(String),
+
+    /// The plugin is not in the manifest anymore.
+    Removed(String),
+
+    /// The plugin is not in the manifest anymore, but it was modified since it was installed,
+    /// so it was left in place and forgotten.
+    Released(String),
+}
+
+impl fmt::Display for PluginChange {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PluginChange::Installed(name) => write!(formatter, "installed {}", name),
+            PluginChange::Updated(name) => write!(formatter, "updated {}", name),
+            PluginChange::Unchanged(name) => write!(formatter, "{} is up to date", name),
+            PluginChange::Removed(name) => write!(formatter, "removed {}", name),
+            PluginChange::Released(name) => {
+                write!(formatter, "left {} in place, as it was modified", name)
+            }
+        }
+    }
+}
+
+/// A plugin recorded in the lockfile, with the checksum of the file that was installed.
+/// While it is being updated, the checksum of the version it replaces is recorded as well, so
+/// an interrupted sync doesn't disown it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct LockedPlugin {
+    source: String,
+    hash: String,
+    previous: Option<String>,
+}
+
+impl LockedPlugin {
+    fn owns(&self, hash: &str) -> bool {
+        self.hash == hash || self.previous.as_deref() == Some(hash)
+    }
+}
+
+pub(crate) fn sync(plugins_directory: &Path, manifest_path: &Path) -> Result<PluginSyncReport> {
+    let manifest = PluginManifest::read(manifest_path)?;
+    let lockfile = manifest_path.with_extension("lock");
+    let (lock_text, locked) = read_lockfile(&lockfile)?;
+    let directory = manifest_path.parent().unwrap_or_else(|| Path::new(""));
+
+    let mut installs = Vec::new();
+    let mut new_lock = BTreeMap::new();
+    let mut changes = Vec::new();
+
+    // everything is checked before changing anything, so a conflict leaves the plugins as
+    // they were
+    for (name, source) in manifest.plugins() {
+        let path = plugins::plugin_file_path(plugins_directory, name)?;
+        let contents = fs::read(source).map_err(|error| Error::PluginSourceUnreadable {
+            path: source.to_owned(),
+            source: error,
+        })?;
+
+        let hash = format!("{:x}", md5::compute(&contents));
+        let installed_hash = file_hash(&path);
+        let locked_plugin = locked.get(name);
+
+        let change = match installed_hash.as_deref() {
+            None => PluginChange::Installed(name.to_owned()),
+            Some(installed) if installed == hash && locked_plugin.is_some() => {
+                PluginChange::Unchanged(name.to_owned())
+            }
+            Some(installed) if locked_plugin.is_some_and(|plugin| plugin.owns(installed)) => {
+                PluginChange::Updated(name.to_owned())
+            }
+            // never overwrite a plugin this manifest didn't install
+            Some(_) => return Err(Error::PluginConflict(path)),
+        };
+
+        if matches!(
+            change,
+            PluginChange::Installed(_) | PluginChange::Updated(_)
+        ) {
+            installs.push((name, contents));
+        }
+
+        let previous = match change {
+            PluginChange::Updated(_) => installed_hash,
+            _ => None,
+        };
+
+        let relative_source = source.strip_prefix(directory).unwrap_or(source);
+        new_lock.insert(
+            name.to_owned(),
+            LockedPlugin {
+                source: relative_source.to_string_lossy().into_owned(),
+                hash,
+                previous,
+            },
+        );
+        changes.push(change);
+    }
+
+    // the plugins about to be installed are locked first, along with the plugins about to be
+    // removed, so the plugins stay owned by this manifest if the sync is interrupted
+    let mut pending_lock = locked.clone();
+    pending_lock.extend(new_lock.clone());
+    write_lockfile(&lockfile, lock_text.as_deref(), &pending_lock)?;
+
+    for (name, contents) in installs {
+        plugins::install(
+            plugins_directory,
+            name,
+            PluginSource::Bytes(&contents),
+            true,
+        )?;
+    }
+
+    for (name, plugin) in &locked {
+        if new_lock.contains_key(name) {
+            continue;
+        }
+
+        let path = plugins::plugin_path(plugins_directory, name)?;
+
+        match file_hash(&path) {
+            // an update interrupted before this plugin was installed leaves the previous one
+            Some(hash) if plugin.owns(&hash) => {
+                plugins::uninstall(plugins_directory, name)?;
+                changes.push(PluginChange::Removed(name.clone()));
+            }
+            Some(_) => changes.push(PluginChange::Released(name.clone())),
+            None => changes.push(PluginChange::Removed(name.clone())),
+        }
+    }
+
+    for plugin in new_lock.values_mut() {
+        plugin.previous = None;
+    }
+
+    write_lockfile(&lockfile, Some(&lockfile_text(&pending_lock)), &new_lock)?;
+
+    changes.sort_by(|a, b| change_name(a).cmp(change_name(b)));
+    Ok(PluginSyncReport { changes })
+}
+
+fn change_name(change: &PluginChange) -> &str {
+    match change {
+        PluginChange::Installed(name)
+        | PluginChange::Updated(name)
+        | PluginChange::Unchanged(name)
+        | PluginChange::Removed(name)
+        | PluginChange::Released(name) => name,
+    }
+}
+
+/// The checksum of an installed plugin file, or `None` when it doesn't exist. Folders and
+/// unreadable files get an empty checksum, which matches no lockfile entry.
+fn file_hash(path: &Path) -> Option<String> {
+    match fs::read(path) {
+        Ok(contents) => Some(format!("{:x}", md5::compute(contents))),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => None,
+        Err(_) => Some(String::new()),
+    }
+}
+
+/// Reads a lockfile, returning its text to avoid rewriting it when nothing changed. A
+/// missing lockfile is empty.
+fn read_lockfile(path: &Path) -> Result<(Option<String>, BTreeMap<String, LockedPlugin>)> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => {
+            return Ok((None, BTreeMap::new()))
+        }
+        Err(source) => {
+            return Err(Error::PluginManifestUnreadable {
+                path: path.to_owned(),
+                source,
+            })
+        }
+    };
+
+    let malformed = |reason: String| {
+        Error::MalformedPluginManifest(format!("{} in `{}`", reason, path.display()))
+    };
+
+    let table: toml::Table = text
+        .parse()
+        .map_err(|error| malformed(format!("{}", error)))?;
+    let mut locked = BTreeMap::new();
+
+    if let Some(plugins) = table.get("plugins").and_then(toml::Value::as_table) {
+        for (name, plugin) in plugins {
+            let field = |key: &str| {
+                plugin
+                    .get(key)
+                    .and_then(toml::Value::as_str)
+                    .map(str::to_owned)
+                    .ok_or_else(|| malformed(format!("missing `{}` for `{}`", key, name)))
+            };
+
+            locked.insert(
+                name.clone(),
+                LockedPlugin {
+                    source: field("source")?,
+                    hash: field("hash")?,
+                    previous: field("previous").ok(),
+                },
+            );
+        }
+    }
+
+    Ok((Some(text), locked))
+}
+
+/// Writes a lockfile, unless it already holds the same plugins.
+fn write_lockfile(
+    path: &Path,
+    current_text: Option<&str>,
+    locked: &BTreeMap<String, LockedPlugin>,
+) -> Result<()> {
+    let text = lockfile_text(locked);
+
+    if current_text == Some(text.as_str()) {
+        return Ok(());
+    }
+
+    fs::write(path, text).map_err(|source| Error::PluginLockfileUnwritable {
+        path: path.to_owned(),
+        source,
+    })
+}
+
+fn lockfile_text(locked: &BTreeMap<String, LockedPlugin>) -> String {
+    let mut plugins = toml::Table::new();
+
+    for (name, plugin) in locked {
+        let mut entry = toml::Table::new();
+        entry.insert("source".to_owned(), plugin.source.clone().into());
+        entry.insert("hash".to_owned(), plugin.hash.clone().into());
+
+        if let Some(previous) = &plugin.previous {
+            entry.insert("previous".to_owned(), previous.clone().into());
+        }
+        plugins.insert(name.clone(), entry.into());
+    }
+
+    let mut table = toml::Table::new();
+    table.insert("plugins".to_owned(), plugins.into());
+
+    format!("{}{}", LOCKFILE_HEADER, table)
+}
@@ -0,0 +1,510 @@
+use std::{
+    fmt,
+    fs::{self, File, OpenOptions},
+    io,
+    path::{Path, PathBuf},
+    process,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::SystemTime,
+};
+
+use crate::{usage::directory_size, Error, PluginModel, Result};
+
+/// The signature binary models start with.
+const BINARY_MODEL_MAGIC: &[u8] = b"<roblox!\x89\xff\r\n\x1a\n";
+
+const UTF8_BOM: &[u8] = b"\xef\xbb\xbf";
+
+/// Numbers the temporary files of the plugins being installed, so installs running at the same
+/// time in a process don't share one.
+static TEMPORARY_FILES: AtomicUsize = AtomicUsize::new(0);
+
+/// The file extensions Roblox Studio loads plugins from.
+const PLUGIN_EXTENSIONS: &[&str] = &["rbxm", "rbxmx", "lua", "luau"];
+
+/// What a plugin is installed from, given to
+/// [`RobloxStudio::install_plugin`](crate::RobloxStudio::install_plugin). Byte slices and
+/// paths convert into it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PluginSource<'a> {
+    /// The contents of the plugin file.
+    Bytes(&'a [u8]),
+
+    /// A plugin file to copy, such as a freshly built `.rbxmx`.
+    File(&'a Path),
+
+    /// A model built from Luau scripts, installed as an `.rbxmx` plugin.
+    Model(&'a PluginModel),
+}
+
+impl<'a> From<&'a [u8]> for PluginSource<'a> {
+    fn from(bytes: &'a [u8]) -> Self {
+        PluginSource::Bytes(bytes)
+    }
+}
+
+impl<'a, const N: usize> From<&'a [u8; N]> for PluginSource<'a> {
+    fn from(bytes: &'a [u8; N]) -> Self {
+        PluginSource::Bytes(bytes)
+    }
+}
+
+impl<'a> From<&'a Vec<u8>> for PluginSource<'a> {
+    fn from(bytes: &'a Vec<u8>) -> Self {
+        PluginSource::Bytes(bytes)
+    }
+}
+
+impl<'a> From<&'a Path> for PluginSource<'a> {
+    fn from(path: &'a Path) -> Self {
+        PluginSource::File(path)
+    }
+}
+
+impl<'a> From<&'a PathBuf> for PluginSource<'a> {
+    fn from(path: &'a PathBuf) -> Self {
+        PluginSource::File(path)
+    }
+}
+
+impl<'a> From<&'a PluginModel> for PluginSource<'a> {
+    fn from(model: &'a PluginModel) -> Self {
+        PluginSource::Model(model)
+    }
+}
+
+/// A plugin found in the user's plugin directory by
+/// [`RobloxStudio::list_plugins`](crate::RobloxStudio::list_plugins), with what could be
+/// learned about it without opening Roblox Studio.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Plugin {
+    name: String,
+    path: PathBuf,
+    kind: PluginKind,
+    size: u64,
+    modified: Option<SystemTime>,
+    hash: Option<String>,
+    issues: Vec<PluginIssue>,
+}
+
+impl Plugin {
+    /// The file name of the plugin, such as `MyPlugin.rbxmx`, which
+    /// [`RobloxStudio::uninstall_plugin`](crate::RobloxStudio::uninstall_plugin) takes.
+    #[must_use]
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The path to the plugin.
+    #[must_use]
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The format of the plugin, detected from its contents rather than its extension.
+    #[must_use]
+    #[inline]
+    pub fn kind(&self) -> PluginKind {
+        self.kind
+    }
+
+    /// The size of the plugin in bytes. For folders, the total size of their files.
+    #[must_use]
+    #[inline]
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// When the plugin was last modified, when the platform reports it.
+    #[must_use]
+    #[inline]
+    pub fn modified(&self) -> Option<SystemTime> {
+        self.modified
+    }
+
+    /// The MD5 checksum of the plugin file, in lowercase hexadecimal. Folders and unreadable
+    /// files have none.
+    #[must_use]
+    #[inline]
+    pub fn hash(&self) -> Option<&str> {
+        self.hash.as_deref()
+    }
+
+    /// The problems found in the plugin, which can stop Roblox Studio from loading it.
+    #[must_use]
+    #[inline]
+    pub fn issues(&self) -> &[PluginIssue] {
+        &self.issues
+    }
+
+    /// Whether any problem was found in the plugin.
+    #[must_use]
+    #[inline]
+    pub fn is_malformed(&self) -> bool {
+        !self.issues.is_empty()
+    }
+}
+
+/// The format of a [`Plugin`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum PluginKind {
+    /// A model in the binary format, usually a `.rbxm` file. It starts with `<roblox!`.
+    BinaryModel,
+
+    /// A model in the XML format, usually a `.rbxmx` file. It starts with `<roblox`.
+    XmlModel,
+
+    /// A Luau script, usually a `.lua` or `.luau` file.
+    Script,
+
+    /// A folder, which Roblox Studio loads as a plugin made of the files inside.
+    Folder,
+
+    /// A file in none of the formats above.
+    Unknown,
+}
+
+/// A problem found in a [`Plugin`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum PluginIssue {
+    /// The plugin file is empty, or the plugin folder has nothing inside.
+    Empty,
+
+    /// The contents of the plugin are in none of the formats of [`PluginKind`].
+    UnknownFormat,
+
+    /// The extension of the file doesn't match its contents, such as an XML model saved as
+    /// `.rbxm`.
+    ExtensionMismatch { extension: String },
+
+    /// The model doesn't end with `</roblox>`, which usually means it was only partially
+    /// written.
+    Truncated,
+
+    /// The script or XML model isn't valid UTF-8.
+    InvalidUtf8,
+
+    /// The plugin couldn't be read.
+    Unreadable { reason: String },
+}
+
+impl fmt::Display for PluginIssue {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PluginIssue::Empty => write!(formatter, "the plugin is empty"),
+            PluginIssue::UnknownFormat => write!(formatter, "the plugin is in an unknown format"),
+            PluginIssue::ExtensionMismatch { extension } => write!(
+                formatter,
+                "the extension `.{}` doesn't match the contents",
+                extension
+            ),
+            PluginIssue::Truncated => write!(formatter, "the model is truncated"),
+            PluginIssue::InvalidUtf8 => write!(formatter, "the plugin is not valid UTF-8"),
+            PluginIssue::Unreadable { reason } => {
+                write!(formatter, "the plugin couldn't be read: {}", reason)
+            }
+        }
+    }
+}
+
+pub(crate) fn install(
+    plugins: &Path,
+    name: &str,
+    source: PluginSource<'_>,
+    overwrite: bool,
+) -> Result<PathBuf> {
+    let path = plugin_file_path(plugins, name)?;
+
+    let is_rbxmx = path
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("rbxmx"));
+
+    if matches!(source, PluginSource::Model(_)) && !is_rbxmx {
+        return Err(Error::InvalidPluginName(name.to_owned()));
+    }
+
+    if !overwrite && fs::symlink_metadata(&path).is_ok() {
+        return Err(Error::PluginAlreadyInstalled(path));
+    }
+
+    fs::create_dir_all(plugins).map_err(|source| Error::PluginInstallFailed {
+        path: plugins.to_owned(),
+        source,
+    })?;
+
+    // written next to the plugin, then moved in place, so Roblox Studio never loads a
+    // partially written plugin
+    let temporary = plugins.join(format!(
+        ".{}.{}.{}.tmp",
+        name,
+        process::id(),
+        TEMPORARY_FILES.fetch_add(1, Ordering::Relaxed)
+    ));
+
+    let written = match source {
+        PluginSource::Bytes(bytes) => fs::write(&temporary, bytes),
+        PluginSource::File(file) => fs::copy(file, &temporary).map(|_| ()),
+        PluginSource::Model(model) => fs::write(&temporary, model.to_rbxmx()),
+    };
+
+    // unlike renaming, linking fails when a plugin was installed since it was checked above.
+    // Filesystems without hard links get a copy, which fails the same way.
+    let moved = written.and_then(|_| {
+        if overwrite {
+            return fs::rename(&temporary, &path);
+        }
+
+        match fs::hard_link(&temporary, &path) {
+            Err(error)
+                if matches!(
+                    error.kind(),
+                    io::ErrorKind::Unsupported | io::ErrorKind::PermissionDenied
+                ) =>
+            {
+                copy_new(&temporary, &path)
+            }
+            linked => linked,
+        }
+    });
+    let _ = fs::remove_file(&temporary);
+
+    match moved {
+        Ok(()) => Ok(path),
+        Err(error) if error.kind() == io::ErrorKind::AlreadyExists => {
+            Err(Error::PluginAlreadyInstalled(path))
+        }
+        Err(source) => Err(Error::PluginInstallFailed { path, source }),
+    }
+}
+
+pub(crate) fn uninstall(plugins: &Path, name: &str) -> Result<()> {
+    let path = plugin_path(plugins, name)?;
+
+    let removed = match fs::symlink_metadata(&path) {
+        Ok(metadata) if metadata.is_dir() => fs::remove_dir_all(&path),
+        Ok(_) => fs::remove_file(&path),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => {
+            return Err(Error::PluginNotInstalled(path))
+        }
+        Err(error) => Err(error),
+    };
+
+    removed.map_err(|source| Error::PluginRemovalFailed { path, source })
+}
+
+pub(crate) fn list(plugins: &Path) -> Result<Vec<Plugin>> {
+    let entries = match fs::read_dir(plugins) {
+        Ok(entries) => entries,
+        // Roblox Studio only creates the directory when it is first opened
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(source) => {
+            return Err(Error::PluginsDirectoryUnreadable {
+                path: plugins.to_owned(),
+                source,
+            })
+        }
+    };
+
+    let mut plugins: Vec<Plugin> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+
+            // hidden files include the temporary files of plugins being installed
+            if name.starts_with('.') {
+                return None;
+            }
+
+            Some(inspect(name, entry.path()))
+        })
+        .collect();
+
+    plugins.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(plugins)
+}
+
+fn inspect(name: String, path: PathBuf) -> Plugin {
+    let metadata = fs::metadata(&path);
+    let modified = metadata
+        .as_ref()
+        .ok()
+        .and_then(|metadata| metadata.modified().ok());
+
+    let mut plugin = Plugin {
+        name,
+        path,
+        kind: PluginKind::Unknown,
+        size: 0,
+        modified,
+        hash: None,
+        issues: Vec::new(),
+    };
+
+    if metadata.is_ok_and(|metadata| metadata.is_dir()) {
+        plugin.kind = PluginKind::Folder;
+        plugin.size = directory_size(&plugin.path);
+
+        let is_empty =
+            fs::read_dir(&plugin.path).map_or(true, |mut entries| entries.next().is_none());
+
+        if is_empty {
+            plugin.issues.push(PluginIssue::Empty);
+        }
+
+        return plugin;
+    }
+
+    let contents = match fs::read(&plugin.path) {
+        Ok(contents) => contents,
+        Err(error) => {
+            plugin.issues.push(PluginIssue::Unreadable {
+                reason: error.to_string(),
+            });
+            return plugin;
+        }
+    };
+
+    plugin.size = contents.len() as u64;
+    plugin.hash = Some(format!("{:x}", md5::compute(&contents)));
+    plugin.kind = detect_kind(&contents);
+
+    if contents.is_empty() {
+        plugin.issues.push(PluginIssue::Empty);
+        return plugin;
+    }
+
+    let is_model = matches!(plugin.kind, PluginKind::BinaryModel | PluginKind::XmlModel);
+
+    match plugin.kind {
+        PluginKind::Unknown => plugin.issues.push(PluginIssue::UnknownFormat),
+        PluginKind::XmlModel | PluginKind::Script if std::str::from_utf8(&contents).is_err() => {
+            plugin.issues.push(PluginIssue::InvalidUtf8)
+        }
+        _ => {}
+    }
+
+    if is_model && !trim_end_whitespace(&contents).ends_with(b"</roblox>") {
+        plugin.issues.push(PluginIssue::Truncated);
+    }
+
+    let extension = Path::new(&plugin.name)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_ascii_lowercase);
+
+    if let Some(extension) = extension {
+        let expected = match extension.as_str() {
+            "rbxm" => Some(PluginKind::BinaryModel),
+            "rbxmx" => Some(PluginKind::XmlModel),
+            "lua" | "luau" => Some(PluginKind::Script),
+            _ => None,
+        };
+
+        let matches = match expected {
+            Some(expected) => expected == plugin.kind,
+            None => true,
+        };
+
+        if !matches && plugin.kind != PluginKind::Unknown {
+            plugin
+                .issues
+                .push(PluginIssue::ExtensionMismatch { extension });
+        }
+    }
+
+    plugin
+}
+
+/// Detects the format of a plugin file from its first bytes.
+fn detect_kind(contents: &[u8]) -> PluginKind {
+    if contents.starts_with(BINARY_MODEL_MAGIC) {
+        return PluginKind::BinaryModel;
+    }
+
+    let text = contents.strip_prefix(UTF8_BOM).unwrap_or(contents);
+    let start = text
+        .iter()
+        .position(|byte| !byte.is_ascii_whitespace())
+        .unwrap_or(text.len());
+    let text = &text[start..];
+
+    if text.starts_with(b"<roblox") || text.starts_with(b"<?xml") {
+        return PluginKind::XmlModel;
+    }
+
+    // scripts are text, which never holds null bytes
+    if !contents.is_empty() && !contents.contains(&0) {
+        return PluginKind::Script;
+    }
+
+    PluginKind::Unknown
+}
+
+fn trim_end_whitespace(contents: &[u8]) -> &[u8] {
+    let end = contents
+        .iter()
+        .rposition(|byte| !byte.is_ascii_whitespace())
+        .map_or(0, |index| index + 1);
+
+    &contents[..end]
+}
+
+/// Copies a file to a path nothing exists at, removing the copy if it can't be completed.
+fn copy_new(source: &Path, destination: &Path) -> io::Result<()> {
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(destination)?;
+
+    io::copy(&mut File::open(source)?, &mut file)
+        .map(|_| ())
+        .inspect_err(|_| {
+            drop(file);
+            let _ = fs::remove_file(destination);
+        })
+}
+
+/// The path of a plugin file, making sure it has an extension Roblox Studio loads plugins
+/// from.
+pub(crate) fn plugin_file_path(plugins: &Path, name: &str) -> Result<PathBuf> {
+    let path = plugin_path(plugins, name)?;
+
+    if is_plugin_file_name(name) {
+        Ok(path)
+    } else {
+        Err(Error::InvalidPluginName(name.to_owned()))
+    }
+}
+
+/// The path of a plugin, making sure its name can't point outside of the plugin directory.
+pub(crate) fn plugin_path(plugins: &Path, name: &str) -> Result<PathBuf> {
+    let is_file_name = !name.is_empty()
+        && !name.starts_with('.')
+        && !name.contains(['/', '\\', ':'])
+        && Path::new(name).file_name() == Some(name.as_ref());
+
+    if is_file_name {
+        Ok(plugins.join(name))
+    } else {
+        Err(Error::InvalidPluginName(name.to_owned()))
+    }
+}
+
+fn is_plugin_file_name(name: &str) -> bool {
+    Path::new(name)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| {
+            PLUGIN_EXTENSIONS
+                .iter()
+                .any(|plugin_extension| extension.eq_ignore_ascii_case(plugin_extension))
+        })
+}
@@ -0,0 +1,210 @@
+use std::{
+    cmp::Reverse,
+    fmt, fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use crate::{manifest::MANIFEST_FILE_NAME, usage::directory_size, Error, Result};
+
+/// The result of [`RobloxStudio::prune_versions`](crate::RobloxStudio::prune_versions), listing
+/// the version directories of a `Versions` directory that were kept and removed, with their
+/// size.
+///
+/// Its [`Display`](fmt::Display) implementation prints one directory per line.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PruneReport {
+    kept: Vec<VersionDirectory>,
+    removed: Vec<VersionDirectory>,
+    dry_run: bool,
+}
+
+impl PruneReport {
+    /// The version directories kept, from most to least preferred.
+    #[must_use]
+    #[inline]
+    pub fn kept(&self) -> &[VersionDirectory] {
+        &self.kept
+    }
+
+    /// The version directories removed, or that would be removed by a dry run.
+    #[must_use]
+    #[inline]
+    pub fn removed(&self) -> &[VersionDirectory] {
+        &self.removed
+    }
+
+    /// The number of bytes freed, or that would be freed by a dry run.
+    #[must_use]
+    pub fn freed(&self) -> u64 {
+        self.removed.iter().map(VersionDirectory::size).sum()
+    }
+
+    /// Whether this comes from
+    /// [`RobloxStudio::prune_versions_dry_run`](crate::RobloxStudio::prune_versions_dry_run),
+    /// in which case nothing was removed.
+    #[must_use]
+    #[inline]
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+}
+
+impl fmt::Display for PruneReport {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let removed = if self.dry_run {
+            "would remove"
+        } else {
+            "removed"
+        };
+
+        for directory in &self.kept {
+            writeln!(formatter, "kept {}", directory)?;
+        }
+
+        for directory in &self.removed {
+            writeln!(formatter, "{} {}", removed, directory)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A directory of a `Versions` directory, in a [`PruneReport`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VersionDirectory {
+    path: PathBuf,
+    size: u64,
+}
+
+impl VersionDirectory {
+    /// The path to the directory.
+    #[must_use]
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The total size of the files in the directory, in bytes.
+    #[must_use]
+    #[inline]
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+}
+
+impl fmt::Display for VersionDirectory {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "`{}` ({} bytes)", self.path.display(), self.size)
+    }
+}
+
+/// Ranks the Roblox Studio version directories next to `active` and removes all but the
+/// first `keep`. `active` and `registry_content`'s version are always kept.
+pub(crate) fn prune(
+    active: &Path,
+    registry_content: Option<PathBuf>,
+    keep: usize,
+    dry_run: bool,
+) -> Result<PruneReport> {
+    let versions = versions_directory(active)
+        .ok_or_else(|| Error::NotInVersionsDirectory(active.to_owned()))?;
+
+    let entries = fs::read_dir(versions).map_err(|source| Error::VersionsDirectoryUnreadable {
+        path: versions.to_owned(),
+        source,
+    })?;
+
+    let registry_version = registry_content.as_deref().and_then(Path::parent);
+
+    let mut directories: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| matches!(entry.file_type(), Ok(kind) if kind.is_dir()))
+        .map(|entry| entry.path())
+        .filter(|path| is_studio_version(path))
+        .collect();
+
+    if !directories.iter().any(|directory| directory == active) {
+        directories.push(active.to_owned());
+    }
+
+    // the version in use and the one Roblox launches come first, then complete versions
+    // from the most recently updated
+    directories.sort_by_cached_key(|directory| {
+        let application = directory.join("RobloxStudioBeta.exe");
+
+        (
+            Reverse(directory == active),
+            Reverse(Some(directory.as_path()) == registry_version),
+            Reverse(application.is_file() && directory.join(MANIFEST_FILE_NAME).is_file()),
+            Reverse(modified(&application)),
+            directory.clone(),
+        )
+    });
+
+    let pinned = if registry_version.is_some_and(|version| version != active) {
+        2
+    } else {
+        1
+    };
+
+    let mut report = PruneReport {
+        dry_run,
+        ..PruneReport::default()
+    };
+
+    for (index, path) in directories.into_iter().enumerate() {
+        let size = directory_size(&path);
+        let keep_directory = index < keep.max(pinned);
+
+        if !keep_directory && !dry_run {
+            fs::remove_dir_all(&path).map_err(|source| Error::VersionRemovalFailed {
+                path: path.clone(),
+                source,
+            })?;
+        }
+
+        let directory = VersionDirectory { path, size };
+
+        if keep_directory {
+            report.kept.push(directory);
+        } else {
+            report.removed.push(directory);
+        }
+    }
+
+    Ok(report)
+}
+
+/// The `Versions` directory a version directory is in, if it is in one.
+pub(crate) fn versions_directory(version: &Path) -> Option<&Path> {
+    version.parent().filter(|versions| {
+        versions
+            .file_name()
+            .is_some_and(|name| name.eq_ignore_ascii_case("Versions"))
+    })
+}
+
+/// Whether a directory of `Versions` belongs to Roblox Studio. The Roblox Player shares the
+/// `Versions` directory, and its versions must never be removed, so only directories holding
+/// `RobloxStudioBeta.exe` or `rbxPkgManifest.txt` count. A Player version still being
+/// installed has neither of them yet.
+pub(crate) fn is_studio_version(directory: &Path) -> bool {
+    let is_version = directory
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.starts_with("version-"));
+
+    is_version
+        && !directory.join("RobloxPlayerBeta.exe").exists()
+        && (directory.join("RobloxStudioBeta.exe").exists()
+            || directory.join(MANIFEST_FILE_NAME).exists())
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
@@ -0,0 +1,271 @@
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+#[cfg(target_os = "windows")]
+use winreg::RegKey;
+
+/// Read access to the string values of the `HKEY_CURRENT_USER` registry hive, where Roblox
+/// Studio stores the location of its `content` directory. [`RobloxStudio::locate_from_registry`]
+/// can find Roblox Studio through any implementation.
+///
+/// [`RobloxStudio::locate_from_registry`]: crate::RobloxStudio::locate_from_registry
+pub trait RegistrySource {
+    /// Reads the string value `name` of `key`, where `key` is relative to `HKEY_CURRENT_USER`,
+    /// such as `Software\Roblox\RobloxStudio`. Missing keys and values are reported as
+    /// [`io::ErrorKind::NotFound`].
+    fn string_value(&self, key: &str, name: &str) -> io::Result<String>;
+
+    /// Converts a path read from the registry to the matching path on this machine. Paths are
+    /// kept as they are by default. Returns `None` if the path can't be converted.
+    fn host_path(&self, registry_path: &str) -> Option<PathBuf> {
+        Some(PathBuf::from(registry_path))
+    }
+}
+
+/// The registry of the current Windows user.
+#[cfg(target_os = "windows")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct WindowsRegistry;
+
+#[cfg(target_os = "windows")]
+impl RegistrySource for WindowsRegistry {
+    fn string_value(&self, key: &str, name: &str) -> io::Result<String> {
+        RegKey::predef(winreg::enums::HKEY_CURRENT_USER)
+            .open_subkey(key)?
+            .get_value(name)
+    }
+}
+
+/// The registry of a Wine prefix, read from the `user.reg` file at its root. Wine saves it
+/// in a text format:
+///
+/// ```text
+/// WINE REGISTRY Version 2
+/// ;; All keys relative to \\User\\S-1-5-21-0-0-0-1000
+///
+/// [Software\\Roblox\\RobloxStudio] 1700000000
+/// #time=1da1b2c3d4e5f60
+/// "ContentFolder"="C:\\users\\roblox\\AppData\\Local\\Roblox\\Versions\\version-abc\\content"
+/// ```
+///
+/// Key and value names are matched case-insensitively, like Windows does. Values that are
+/// not strings (`dword:`, `hex:`...) are skipped. Windows paths read from it are converted to
+/// paths inside the prefix.
+#[derive(Debug, Clone)]
+pub struct WineRegistry {
+    prefix: PathBuf,
+    keys: HashMap<String, HashMap<String, String>>,
+}
+
+impl WineRegistry {
+    /// Reads the `user.reg` file of the given Wine prefix.
+    pub fn open<P: Into<PathBuf>>(prefix: P) -> io::Result<WineRegistry> {
+        let prefix = prefix.into();
+        let text = fs::read_to_string(prefix.join("user.reg"))?;
+
+        Ok(Self::parse(prefix, &text))
+    }
+
+    /// Parses the contents of a `user.reg` file belonging to the given Wine prefix. Lines that
+    /// can't be parsed are ignored.
+    pub fn parse<P: Into<PathBuf>>(prefix: P, text: &str) -> WineRegistry {
+        let mut keys: HashMap<String, HashMap<String, String>> = HashMap::new();
+        let mut current_key: Option<String> = None;
+
+        for line in text.lines() {
+            let line = line.trim_start();
+
+            if let Some(key_line) = line.strip_prefix('[') {
+                // the key name is followed by `]` and the key's modification time
+                current_key = key_line
+                    .rfind(']')
+                    .map(|end| unescape(&key_line[..end]).to_lowercase());
+
+                continue;
+            }
+
+            let key = match &current_key {
+                Some(key) => key,
+                None => continue,
+            };
+
+            if let Some((name, value)) = parse_value_line(line) {
+                keys.entry(key.clone())
+                    .or_default()
+                    .insert(name.to_lowercase(), value);
+            }
+        }
+
+        WineRegistry {
+            prefix: prefix.into(),
+            keys,
+        }
+    }
+
+    /// The Wine prefix this registry belongs to.
+    #[must_use]
+    #[inline]
+    pub fn prefix(&self) -> &Path {
+        &self.prefix
+    }
+}
+
+impl RegistrySource for WineRegistry {
+    fn string_value(&self, key: &str, name: &str) -> io::Result<String> {
+        self.keys
+            .get(&key.to_lowercase())
+            .ok_or_else(|| not_found(format!("registry key `{}` not found", key)))?
+            .get(&name.to_lowercase())
+            .cloned()
+            .ok_or_else(|| not_found(format!("registry value `{}` not found in `{}`", name, key)))
+    }
+
+    /// Converts an absolute Windows path (`C:\users\...`) to the matching path inside the
+    /// prefix. The `C:` drive is the prefix's `drive_c` directory, other drives are reached
+    /// through the links in its `dosdevices` directory.
+    fn host_path(&self, registry_path: &str) -> Option<PathBuf> {
+        let mut components = registry_path
+            .split(['\\', '/'])
+            .filter(|component| !component.is_empty());
+
+        let drive = components
+            .next()?
+            .strip_suffix(':')
+            .filter(|letter| letter.len() == 1 && letter.chars().all(|c| c.is_ascii_alphabetic()))?
+            .to_ascii_lowercase();
+
+        let drive_root = if drive == "c" {
+            self.prefix.join("drive_c")
+        } else {
+            self.prefix.join("dosdevices").join(format!("{}:", drive))
+        };
+
+        Some(components.fold(drive_root, |path, component| path.join(component)))
+    }
+}
+
+/// A registry held in memory, mostly useful to test code relying on a [`RegistrySource`].
+#[derive(Debug, Default, Clone)]
+pub struct MemoryRegistry {
+    values: HashMap<(String, String), String>,
+}
+
+impl MemoryRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the string value `name` of `key`.
+    pub fn with_value<V: Into<String>>(mut self, key: &str, name: &str, value: V) -> Self {
+        self.values
+            .insert((key.to_lowercase(), name.to_lowercase()), value.into());
+        self
+    }
+}
+
+impl RegistrySource for MemoryRegistry {
+    fn string_value(&self, key: &str, name: &str) -> io::Result<String> {
+        self.values
+            .get(&(key.to_lowercase(), name.to_lowercase()))
+            .cloned()
+            .ok_or_else(|| not_found(format!("registry value `{}` not found in `{}`", name, key)))
+    }
+}
+
+fn not_found(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::NotFound, message)
+}
+
+/// Parses `"Name"="value"`, `"Name"=str(2):"value"` or `@="value"`.
+fn parse_value_line(line: &str) -> Option<(String, String)> {
+    let (name, rest) = if let Some(rest) = line.strip_prefix('@') {
+        ("@".to_owned(), rest)
+    } else {
+        let (name, rest) = parse_quoted(line)?;
+        (unescape(name), rest)
+    };
+
+    let mut data = rest.trim_start().strip_prefix('=')?.trim_start();
+
+    if let Some(typed) = data.strip_prefix("str(") {
+        data = &typed[typed.find("):")? + 2..];
+    }
+
+    let (value, _) = parse_quoted(data)?;
+    Some((name, unescape(value)))
+}
+
+/// Splits a string starting with a quoted, escaped string into the raw contents of the
+/// quotes and what follows them.
+fn parse_quoted(text: &str) -> Option<(&str, &str)> {
+    let text = text.strip_prefix('"')?;
+    let mut escaped = false;
+
+    for (index, character) in text.char_indices() {
+        match character {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => return Some((&text[..index], &text[index + 1..])),
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Resolves the C-style escapes Wine uses in key names and string values.
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut characters = text.chars().peekable();
+
+    while let Some(character) = characters.next() {
+        if character != '\\' {
+            unescaped.push(character);
+            continue;
+        }
+
+        match characters.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some('t') => unescaped.push('\t'),
+            Some('x') => {
+                let mut code = 0;
+
+                for _ in 0..4 {
+                    match characters.peek().and_then(|digit| digit.to_digit(16)) {
+                        Some(digit) => {
+                            code = code * 16 + digit;
+                            characters.next();
+                        }
+                        None => break,
+                    }
+                }
+
+                unescaped.extend(char::from_u32(code));
+            }
+            Some(digit @ '0'..='7') => {
+                let mut code = digit.to_digit(8).unwrap_or_default();
+
+                for _ in 0..2 {
+                    match characters.peek().and_then(|digit| digit.to_digit(8)) {
+                        Some(digit) => {
+                            code = code * 8 + digit;
+                            characters.next();
+                        }
+                        None => break,
+                    }
+                }
+
+                unescaped.extend(char::from_u32(code));
+            }
+            Some(other) => unescaped.push(other),
+            None => unescaped.push('\\'),
+        }
+    }
+
+    unescaped
+}
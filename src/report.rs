@@ -0,0 +1,125 @@
+use std::{fmt, path::PathBuf};
+
+use crate::StudioSource;
+
+/// Everything that was checked while locating Roblox Studio, in order. Returned by
+/// [`StudioLocator::locate_with_report`](crate::StudioLocator::locate_with_report) to explain
+/// why an installation was picked, or why none was found.
+///
+/// Its [`Display`](fmt::Display) implementation prints one step per line, and its
+/// [`steps`](LocateReport::steps) can be inspected or logged individually.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LocateReport {
+    steps: Vec<LocateStep>,
+}
+
+impl LocateReport {
+    /// The steps taken, in order.
+    #[must_use]
+    #[inline]
+    pub fn steps(&self) -> &[LocateStep] {
+        &self.steps
+    }
+
+    pub(crate) fn push(&mut self, step: LocateStep) {
+        self.steps.push(step);
+    }
+}
+
+impl fmt::Display for LocateReport {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for step in &self.steps {
+            match step {
+                LocateStep::SearchSource(_) => writeln!(formatter, "{}", step)?,
+                _ => writeln!(formatter, "  {}", step)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A single step of a [`LocateReport`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum LocateStep {
+    /// Started searching a source.
+    SearchSource(StudioSource),
+
+    /// The source does not apply here, such as the registry on a platform without one.
+    SourceNotApplicable {
+        source: StudioSource,
+        reason: String,
+    },
+
+    /// The source did not lead to any installation.
+    SourceFailed { source: StudioSource, error: String },
+
+    /// Read an environment variable. `value` is `None` when it is not defined.
+    EnvironmentVariable { name: String, value: Option<String> },
+
+    /// Read a registry value. `value` is `None` when it could not be read.
+    RegistryValue {
+        key: String,
+        name: String,
+        value: Option<String>,
+    },
+
+    /// Read a file, such as a configuration file or a Wine registry hive.
+    FileRead(PathBuf),
+
+    /// Searched a directory for installations.
+    CandidateDirectory(PathBuf),
+
+    /// A file or directory that was expected does not exist.
+    MissingFile(PathBuf),
+
+    /// Found an installation, given by the path to its executable.
+    CandidateFound(PathBuf),
+
+    /// An installation was found, but is not kept.
+    CandidateRejected { path: PathBuf, reason: String },
+
+    /// Picked the installation with the given executable.
+    Selected(PathBuf),
+}
+
+impl fmt::Display for LocateStep {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LocateStep::SearchSource(source) => write!(formatter, "Searching {}", source),
+            LocateStep::SourceNotApplicable { reason, .. } => {
+                write!(formatter, "Skipped: {}", reason)
+            }
+            LocateStep::SourceFailed { error, .. } => write!(formatter, "Failed: {}", error),
+            LocateStep::EnvironmentVariable { name, value } => match value {
+                Some(value) => write!(formatter, "Environment variable `{}` is `{}`", name, value),
+                None => write!(formatter, "Environment variable `{}` is not defined", name),
+            },
+            LocateStep::RegistryValue { key, name, value } => match value {
+                Some(value) => write!(
+                    formatter,
+                    "Registry value `{}` of `{}` is `{}`",
+                    name, key, value
+                ),
+                None => write!(
+                    formatter,
+                    "Registry value `{}` of `{}` could not be read",
+                    name, key
+                ),
+            },
+            LocateStep::FileRead(path) => write!(formatter, "Read `{}`", path.display()),
+            LocateStep::CandidateDirectory(path) => {
+                write!(formatter, "Looking in `{}`", path.display())
+            }
+            LocateStep::MissingFile(path) => write!(formatter, "Missing `{}`", path.display()),
+            LocateStep::CandidateFound(path) => write!(formatter, "Found `{}`", path.display()),
+            LocateStep::CandidateRejected { path, reason } => {
+                write!(formatter, "Rejected `{}`: {}", path.display(), reason)
+            }
+            LocateStep::Selected(path) => write!(formatter, "Selected `{}`", path.display()),
+        }
+    }
+}
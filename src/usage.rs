@@ -0,0 +1,192 @@
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    prune::{is_studio_version, versions_directory},
+    Platform, RobloxStudio, ROBLOX_STUDIO_BUNDLE_IDENTIFIER,
+};
+
+/// The caches Roblox keeps in the `Roblox` directory on Windows, next to `Versions`.
+const WINDOWS_CACHES: &[&str] = &["Downloads", "http", "rbx-storage"];
+
+/// The bytes used by a Roblox Studio installation, by kind. Returned by
+/// [`RobloxStudio::disk_usage`].
+///
+/// Its [`Display`](fmt::Display) implementation prints one kind per line.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DiskUsage {
+    application: u64,
+    content: u64,
+    built_in_plugins: u64,
+    plugins: u64,
+    other_versions: u64,
+    logs: u64,
+    caches: u64,
+}
+
+impl DiskUsage {
+    /// The rest of the version directory or bundle, such as the executable and its
+    /// libraries.
+    #[must_use]
+    #[inline]
+    pub fn application(&self) -> u64 {
+        self.application
+    }
+
+    /// The directory of [`RobloxStudio::content_path`].
+    #[must_use]
+    #[inline]
+    pub fn content(&self) -> u64 {
+        self.content
+    }
+
+    /// The directory of [`RobloxStudio::built_in_plugins_path`].
+    #[must_use]
+    #[inline]
+    pub fn built_in_plugins(&self) -> u64 {
+        self.built_in_plugins
+    }
+
+    /// The directory of [`RobloxStudio::plugins_path`].
+    #[must_use]
+    #[inline]
+    pub fn plugins(&self) -> u64 {
+        self.plugins
+    }
+
+    /// The other Roblox Studio versions of the `Versions` directory, which
+    /// [`RobloxStudio::prune_versions`] can remove.
+    #[must_use]
+    #[inline]
+    pub fn other_versions(&self) -> u64 {
+        self.other_versions
+    }
+
+    /// The logs Roblox writes, in `Roblox\logs` on Windows and `~/Library/Logs/Roblox` on
+    /// macOS.
+    #[must_use]
+    #[inline]
+    pub fn logs(&self) -> u64 {
+        self.logs
+    }
+
+    /// The downloads and HTTP caches of Roblox, in the `Roblox` directory on Windows and in
+    /// `~/Library/Caches` on macOS.
+    #[must_use]
+    #[inline]
+    pub fn caches(&self) -> u64 {
+        self.caches
+    }
+
+    /// The sum of every kind.
+    #[must_use]
+    pub fn total(&self) -> u64 {
+        self.application
+            + self.content
+            + self.built_in_plugins
+            + self.plugins
+            + self.other_versions
+            + self.logs
+            + self.caches
+    }
+}
+
+impl fmt::Display for DiskUsage {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (kind, bytes) in [
+            ("application", self.application),
+            ("content", self.content),
+            ("built-in plugins", self.built_in_plugins),
+            ("plugins", self.plugins),
+            ("other versions", self.other_versions),
+            ("logs", self.logs),
+            ("caches", self.caches),
+        ] {
+            writeln!(formatter, "{}: {} bytes", kind, bytes)?;
+        }
+
+        writeln!(formatter, "total: {} bytes", self.total())
+    }
+}
+
+pub(crate) fn disk_usage(studio: &RobloxStudio) -> DiskUsage {
+    let content = directory_size(&studio.content);
+    let built_in_plugins = directory_size(&studio.built_in_plugins);
+    let (logs, caches) = log_and_cache_directories(studio);
+
+    let other_versions = match versions_directory(&studio.root) {
+        Some(versions) if studio.platform == Platform::Windows => fs::read_dir(versions)
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok())
+                    .map(|entry| entry.path())
+                    .filter(|path| *path != studio.root && path.is_dir())
+                    .filter(|path| is_studio_version(path))
+                    .map(|path| directory_size(&path))
+                    .sum()
+            })
+            .unwrap_or(0),
+        _ => 0,
+    };
+
+    DiskUsage {
+        application: directory_size(&studio.root).saturating_sub(content + built_in_plugins),
+        content,
+        built_in_plugins,
+        plugins: directory_size(&studio.plugins),
+        other_versions,
+        logs: logs.iter().map(|directory| directory_size(directory)).sum(),
+        caches: caches
+            .iter()
+            .map(|directory| directory_size(directory))
+            .sum(),
+    }
+}
+
+/// Where Roblox writes its logs and caches. On Windows they are in the `Roblox` directory
+/// holding the user plugins, so the same rules apply to Wine prefixes.
+fn log_and_cache_directories(studio: &RobloxStudio) -> (Vec<PathBuf>, Vec<PathBuf>) {
+    match studio.platform {
+        Platform::Windows => match studio.plugins.parent() {
+            Some(roblox) => (
+                vec![roblox.join("logs")],
+                WINDOWS_CACHES
+                    .iter()
+                    .map(|cache| roblox.join(cache))
+                    .collect(),
+            ),
+            None => (Vec::new(), Vec::new()),
+        },
+        Platform::MacOs => (
+            dirs::home_dir()
+                .map(|home| home.join("Library").join("Logs").join("Roblox"))
+                .into_iter()
+                .collect(),
+            dirs::cache_dir()
+                .map(|caches| caches.join(ROBLOX_STUDIO_BUNDLE_IDENTIFIER))
+                .into_iter()
+                .collect(),
+        ),
+    }
+}
+
+/// The total size of the files in a directory and its subdirectories. Unreadable entries
+/// count as empty.
+pub(crate) fn directory_size(directory: &Path) -> u64 {
+    let entries = match fs::read_dir(directory) {
+        Ok(entries) => entries,
+        Err(_) => return 0,
+    };
+
+    entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| match entry.file_type() {
+            Ok(kind) if kind.is_dir() => directory_size(&entry.path()),
+            Ok(kind) if kind.is_file() => entry.metadata().map_or(0, |metadata| metadata.len()),
+            _ => 0,
+        })
+        .sum()
+}
@@ -0,0 +1,274 @@
+use std::{
+    fmt,
+    fs::{self, File},
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    manifest::{PackageManifest, MANIFEST_FILE_NAME},
+    usage::directory_size,
+    Package, Result,
+};
+
+/// The result of [`RobloxStudio::verify`](crate::RobloxStudio::verify), comparing a version
+/// directory to its `rbxPkgManifest.txt`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IntegrityReport {
+    issues: Vec<IntegrityIssue>,
+    unchecked_packages: Vec<String>,
+}
+
+impl IntegrityReport {
+    /// Whether no issue was found.
+    #[must_use]
+    #[inline]
+    pub fn is_intact(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// The issues found, in the order of the manifest.
+    #[must_use]
+    #[inline]
+    pub fn issues(&self) -> &[IntegrityIssue] {
+        &self.issues
+    }
+
+    /// The packages that could not be checked, because this crate doesn't know where they
+    /// are extracted, or because they are extracted directly in the version directory and
+    /// their zip is not there anymore.
+    #[must_use]
+    #[inline]
+    pub fn unchecked_packages(&self) -> &[String] {
+        &self.unchecked_packages
+    }
+}
+
+/// A difference between a version directory and its `rbxPkgManifest.txt`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum IntegrityIssue {
+    /// A file every version has, such as `RobloxStudioBeta.exe`, is missing.
+    MissingFile(PathBuf),
+
+    /// Nothing was extracted from a package.
+    MissingPackage {
+        package: String,
+        destination: PathBuf,
+    },
+
+    /// The files extracted from a package don't add up to the size listed in the manifest,
+    /// which usually means the update extracting them was interrupted.
+    SizeMismatch {
+        package: String,
+        destination: PathBuf,
+        expected: u64,
+        actual: u64,
+    },
+
+    /// The zip of a package left in the version directory doesn't match the checksum of the
+    /// manifest.
+    ChecksumMismatch {
+        package: String,
+        expected: String,
+        actual: String,
+    },
+
+    /// A directory no package of the manifest is extracted to, such as a leftover from
+    /// another version.
+    ExtraDirectory(PathBuf),
+}
+
+impl fmt::Display for IntegrityIssue {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IntegrityIssue::MissingFile(path) => {
+                write!(formatter, "`{}` is missing", path.display())
+            }
+            IntegrityIssue::MissingPackage {
+                package,
+                destination,
+            } => write!(
+                formatter,
+                "`{}` was not extracted to `{}`",
+                package,
+                destination.display()
+            ),
+            IntegrityIssue::SizeMismatch {
+                package,
+                destination,
+                expected,
+                actual,
+            } => write!(
+                formatter,
+                "`{}` holds {} bytes instead of the {} bytes of `{}`",
+                destination.display(),
+                actual,
+                expected,
+                package
+            ),
+            IntegrityIssue::ChecksumMismatch {
+                package,
+                expected,
+                actual,
+            } => write!(
+                formatter,
+                "`{}` has the checksum {} instead of {}",
+                package, actual, expected
+            ),
+            IntegrityIssue::ExtraDirectory(path) => {
+                write!(formatter, "`{}` is not part of the version", path.display())
+            }
+        }
+    }
+}
+
+/// Compares a version directory with the `rbxPkgManifest.txt` it contains.
+pub(crate) fn verify(root: &Path, application: &Path) -> Result<IntegrityReport> {
+    let manifest = PackageManifest::read(root.join(MANIFEST_FILE_NAME))?;
+    let mut report = IntegrityReport::default();
+
+    if !application.is_file() {
+        report
+            .issues
+            .push(IntegrityIssue::MissingFile(application.to_owned()));
+    }
+
+    let mut destinations = Vec::new();
+
+    for package in manifest.packages() {
+        let zip_checked = check_zip(root, package, &mut report.issues);
+
+        match package.destination() {
+            Some(destination) if destination.as_os_str().is_empty() => {
+                if !zip_checked {
+                    report.unchecked_packages.push(package.name().to_owned());
+                }
+            }
+            Some(destination) => {
+                check_destination(root, package, &destination, &mut report.issues);
+                destinations.push(destination);
+            }
+            None => report.unchecked_packages.push(package.name().to_owned()),
+        }
+    }
+
+    report.issues.extend(
+        extra_directories(root, &destinations)
+            .into_iter()
+            .map(IntegrityIssue::ExtraDirectory),
+    );
+
+    Ok(report)
+}
+
+/// Checks the zip of a package when it was left in the version directory. Returns whether
+/// there was one.
+fn check_zip(root: &Path, package: &Package, issues: &mut Vec<IntegrityIssue>) -> bool {
+    let zip = root.join(package.name());
+
+    if !zip.is_file() {
+        return false;
+    }
+
+    let actual = md5_checksum(&zip).unwrap_or_default();
+
+    if actual != package.checksum() {
+        issues.push(IntegrityIssue::ChecksumMismatch {
+            package: package.name().to_owned(),
+            expected: package.checksum().to_owned(),
+            actual,
+        });
+    }
+
+    true
+}
+
+fn check_destination(
+    root: &Path,
+    package: &Package,
+    destination: &Path,
+    issues: &mut Vec<IntegrityIssue>,
+) {
+    let actual = directory_size(&root.join(destination));
+
+    if actual == package.size() {
+        return;
+    }
+
+    let package_name = package.name().to_owned();
+    let destination = destination.to_owned();
+
+    issues.push(if actual == 0 {
+        IntegrityIssue::MissingPackage {
+            package: package_name,
+            destination,
+        }
+    } else {
+        IntegrityIssue::SizeMismatch {
+            package: package_name,
+            destination,
+            expected: package.size(),
+            actual,
+        }
+    });
+}
+
+/// The directories next to package destinations which are not package destinations
+/// themselves. Packages extracted directly in the version directory can contain anything, so
+/// the version directory itself is not searched.
+fn extra_directories(root: &Path, destinations: &[PathBuf]) -> Vec<PathBuf> {
+    let mut parents: Vec<&Path> = destinations
+        .iter()
+        .filter_map(|destination| destination.parent())
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .collect();
+
+    parents.sort();
+    parents.dedup();
+
+    let mut extra = Vec::new();
+
+    for parent in parents {
+        let entries = match fs::read_dir(root.join(parent)) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+
+        let mut directories: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| matches!(entry.file_type(), Ok(kind) if kind.is_dir()))
+            .map(|entry| parent.join(entry.file_name()))
+            .filter(|directory| {
+                !destinations
+                    .iter()
+                    .any(|destination| destination.starts_with(directory))
+            })
+            .collect();
+
+        directories.sort();
+        extra.extend(directories);
+    }
+
+    extra
+}
+
+pub(crate) fn md5_checksum(path: &Path) -> io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut context = md5::Context::new();
+    let mut buffer = vec![0; 64 * 1024];
+
+    loop {
+        let read = file.read(&mut buffer)?;
+
+        if read == 0 {
+            break;
+        }
+
+        context.consume(&buffer[..read]);
+    }
+
+    Ok(format!("{:x}", context.compute()))
+}
@@ -0,0 +1,212 @@
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    io,
+    path::Path,
+    str::FromStr,
+};
+
+use crate::{BundleInfo, Error, Platform, Result, VersionResource};
+
+/// The version of a Roblox Studio installation, returned by
+/// [`RobloxStudio::version`](crate::RobloxStudio::version).
+///
+/// It holds the hash of the version directory (`version-<hash>`) and the dotted version
+/// (`0.612.0.6120532`), when they are known. Versions are compared by their dotted version
+/// only, so the most recent release is the greatest and two versions with the same dotted
+/// version are equal whatever their hash. Hashes aren't ordered, so a version without a
+/// dotted version can't be compared to any other: it is only equal to a version with the
+/// same hash and no dotted version either.
+///
+/// Versions can be parsed from either form, and a dotted version is useful to require a
+/// minimum version:
+///
+/// ```no_run
+/// use roblox_install::{RobloxStudio, StudioVersion};
+///
+/// let minimum: StudioVersion = "0.600".parse()?;
+///
+/// if RobloxStudio::locate()?.version()?.is_at_least(&minimum) == Some(false) {
+///     eprintln!("Roblox Studio needs to be updated");
+/// }
+/// # Ok::<(), roblox_install::Error>(())
+/// ```
+#[derive(Debug, Clone, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StudioVersion {
+    numbers: Option<Vec<u32>>,
+    hash: Option<String>,
+}
+
+impl StudioVersion {
+    /// The hash of the version, which names its directory on Windows (`version-<hash>`).
+    #[must_use]
+    #[inline]
+    pub fn hash(&self) -> Option<&str> {
+        self.hash.as_deref()
+    }
+
+    /// The numbers of the dotted version, such as `[0, 612, 0, 6120532]`.
+    #[must_use]
+    #[inline]
+    pub fn numbers(&self) -> Option<&[u32]> {
+        self.numbers.as_deref()
+    }
+
+    /// The name of the version's directory in `Versions`, such as `version-1a2b3c4d5e6f7a8b`.
+    #[must_use]
+    pub fn folder_name(&self) -> Option<String> {
+        self.hash.as_ref().map(|hash| format!("version-{}", hash))
+    }
+
+    /// Whether this version is `minimum` or a more recent one, or `None` when either of them
+    /// has no dotted version to compare.
+    #[must_use]
+    pub fn is_at_least(&self, minimum: &StudioVersion) -> Option<bool> {
+        self.partial_cmp(minimum)
+            .map(|ordering| ordering != Ordering::Less)
+    }
+
+    pub(crate) fn read(root: &Path, application: &Path, platform: Platform) -> Result<Self> {
+        let hash = root
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(folder_hash)
+            .map(str::to_owned);
+
+        let numbers = match platform {
+            Platform::Windows => executable_numbers(application),
+            Platform::MacOs => bundle_numbers(root),
+        };
+
+        // the hash is enough to tell the version apart when the dotted version can't be read
+        let numbers = match numbers {
+            Ok(numbers) => numbers,
+            Err(_) if hash.is_some() => None,
+            Err(error) => return Err(error),
+        };
+
+        if hash.is_none() && numbers.is_none() {
+            return Err(Error::VersionNotFound(root.to_owned()));
+        }
+
+        Ok(StudioVersion { numbers, hash })
+    }
+}
+
+impl PartialEq for StudioVersion {
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.numbers, &other.numbers) {
+            (Some(numbers), Some(other_numbers)) => numbers == other_numbers,
+            (None, None) => self.hash == other.hash,
+            _ => false,
+        }
+    }
+}
+
+impl PartialOrd for StudioVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (&self.numbers, &other.numbers) {
+            (Some(numbers), Some(other_numbers)) => Some(numbers.cmp(other_numbers)),
+            _ if self == other => Some(Ordering::Equal),
+            _ => None,
+        }
+    }
+}
+
+impl Hash for StudioVersion {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // only what equality looks at
+        match &self.numbers {
+            Some(numbers) => numbers.hash(state),
+            None => self.hash.hash(state),
+        }
+    }
+}
+
+impl FromStr for StudioVersion {
+    type Err = Error;
+
+    /// Parses either a dotted version (`0.612.0.6120532`) or the name of a version directory
+    /// (`version-1a2b3c4d5e6f7a8b`).
+    fn from_str(text: &str) -> Result<Self> {
+        let text = text.trim();
+
+        if let Some(hash) = folder_hash(text) {
+            return Ok(StudioVersion {
+                numbers: None,
+                hash: Some(hash.to_owned()),
+            });
+        }
+
+        match parse_numbers(text) {
+            Some(numbers) => Ok(StudioVersion {
+                numbers: Some(numbers),
+                hash: None,
+            }),
+            None => Err(Error::InvalidVersion(text.to_owned())),
+        }
+    }
+}
+
+impl fmt::Display for StudioVersion {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(numbers) = &self.numbers {
+            let numbers: Vec<String> = numbers.iter().map(u32::to_string).collect();
+            write!(formatter, "{}", numbers.join("."))?;
+
+            if let Some(hash) = &self.hash {
+                write!(formatter, " (version-{})", hash)?;
+            }
+        } else if let Some(hash) = &self.hash {
+            write!(formatter, "version-{}", hash)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn folder_hash(name: &str) -> Option<&str> {
+    name.strip_prefix("version-")
+        .filter(|hash| !hash.is_empty() && hash.chars().all(|c| c.is_ascii_alphanumeric()))
+}
+
+/// Parses a dotted version. Windows version resources usually separate the numbers with
+/// commas instead (`0, 612, 0, 6120532`), which is accepted too.
+fn parse_numbers(text: &str) -> Option<Vec<u32>> {
+    text.split(['.', ','])
+        .map(|number| number.trim().parse().ok())
+        .collect()
+}
+
+fn executable_numbers(application: &Path) -> Result<Option<Vec<u32>>> {
+    let resource = VersionResource::read(application)?;
+
+    let numbers = resource.file_version().and_then(parse_numbers).or_else(|| {
+        resource
+            .fixed_file_version()
+            .map(|numbers| numbers.iter().copied().map(u32::from).collect())
+    });
+
+    Ok(numbers)
+}
+
+fn bundle_numbers(root: &Path) -> Result<Option<Vec<u32>>> {
+    let info = match BundleInfo::read(root) {
+        Ok(info) => info,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(source) => {
+            return Err(Error::VersionUnreadable {
+                path: BundleInfo::path(root),
+                source,
+            })
+        }
+    };
+
+    Ok(info
+        .short_version()
+        .into_iter()
+        .chain(info.version())
+        .find_map(parse_numbers))
+}
@@ -0,0 +1,154 @@
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+use std::env;
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{wine, Error, LocateReport, LocateStep, Result, RobloxStudio};
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const FLATPAK_ID: &str = "org.vinegarhq.Vinegar";
+
+/// The data directory and configuration file of every Vinegar installation that may exist
+/// for the current user: the native one first, then the Flatpak one.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub(crate) fn default_directories() -> Vec<(PathBuf, PathBuf)> {
+    let mut directories = Vec::new();
+
+    if let (Some(data), Some(config)) = (
+        xdg_directory("XDG_DATA_HOME", &[".local", "share"]),
+        xdg_directory("XDG_CONFIG_HOME", &[".config"]),
+    ) {
+        directories.push((data.join("vinegar"), config.join("vinegar").join("config.toml")));
+    }
+
+    if let Some(home) = dirs::home_dir() {
+        let flatpak = home.join(".var").join("app").join(FLATPAK_ID);
+
+        directories.push((
+            flatpak.join("data").join("vinegar"),
+            flatpak.join("config").join("vinegar").join("config.toml"),
+        ));
+    }
+
+    directories
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn xdg_directory(variable: &str, home_relative: &[&str]) -> Option<PathBuf> {
+    match env::var_os(variable) {
+        Some(directory) if !directory.is_empty() => Some(PathBuf::from(directory)),
+        _ => dirs::home_dir()
+            .map(|home| home_relative.iter().fold(home, |path, part| path.join(part))),
+    }
+}
+
+/// The Roblox Studio versions and Wine prefix managed by Vinegar.
+pub(crate) struct VinegarStudio {
+    /// Version directories. Only holds the version Vinegar launches when it is known.
+    pub versions: Vec<PathBuf>,
+
+    /// The `Roblox` directory in the local application data of the Studio prefix.
+    pub roblox: PathBuf,
+}
+
+/// Finds the Roblox Studio managed by the Vinegar installation with the given data directory
+/// and configuration file. The version launched is `studio.forced_version` from the
+/// configuration when set, or otherwise the version Vinegar last installed according to its
+/// `state.toml`. When neither is known, every installed version is returned.
+pub(crate) fn locate(
+    data: &Path,
+    config: &Path,
+    report: &mut LocateReport,
+) -> Result<VinegarStudio> {
+    if !data.is_dir() {
+        report.push(LocateStep::MissingFile(data.to_owned()));
+        return Err(Error::NotInstalled);
+    }
+
+    let versions_directory = data.join("versions");
+    report.push(LocateStep::CandidateDirectory(versions_directory.clone()));
+    let mut versions = RobloxStudio::version_directories(&versions_directory, report)?;
+
+    let managed_version = match forced_version(config, report)? {
+        Some(version) => Some(version),
+        None => state_version(&data.join("state.toml"), report),
+    };
+
+    if let Some(managed_version) = managed_version {
+        let managed = versions_directory.join(managed_version);
+
+        versions.retain(|version| {
+            if *version == managed {
+                return true;
+            }
+
+            report.push(LocateStep::CandidateRejected {
+                path: version.join("RobloxStudioBeta.exe"),
+                reason: "not the version Vinegar launches".to_owned(),
+            });
+
+            false
+        });
+    }
+
+    if versions.is_empty() {
+        return Err(Error::NoVersionFound(versions_directory));
+    }
+
+    let prefix = data.join("prefixes").join("studio");
+    let roblox = wine::user_roblox_directory(&prefix).ok_or(Error::PluginsDirectoryNotFound)?;
+
+    Ok(VinegarStudio { versions, roblox })
+}
+
+fn forced_version(config: &Path, report: &mut LocateReport) -> Result<Option<String>> {
+    let config_text = match fs::read_to_string(config) {
+        Ok(text) => text,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => {
+            report.push(LocateStep::MissingFile(config.to_owned()));
+            return Ok(None);
+        }
+        Err(error) => {
+            return Err(Error::VinegarConfigError(format!(
+                "could not read `{}` ({})",
+                config.display(),
+                error
+            )))
+        }
+    };
+
+    report.push(LocateStep::FileRead(config.to_owned()));
+
+    let config_table: toml::Table = config_text.parse().map_err(|error| {
+        Error::VinegarConfigError(format!("could not parse `{}` ({})", config.display(), error))
+    })?;
+
+    Ok(studio_string(&config_table, "forced_version"))
+}
+
+fn state_version(state: &Path, report: &mut LocateReport) -> Option<String> {
+    // the state is written by Vinegar itself, so an unreadable one is only treated as unknown
+    let state_text = match fs::read_to_string(state) {
+        Ok(text) => text,
+        Err(_) => {
+            report.push(LocateStep::MissingFile(state.to_owned()));
+            return None;
+        }
+    };
+
+    report.push(LocateStep::FileRead(state.to_owned()));
+
+    let state_table: toml::Table = state_text.parse().ok()?;
+    studio_string(&state_table, "version")
+}
+
+fn studio_string(table: &toml::Table, key: &str) -> Option<String> {
+    table
+        .get("studio")?
+        .get(key)?
+        .as_str()
+        .filter(|value| !value.is_empty())
+        .map(str::to_owned)
+}
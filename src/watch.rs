@@ -0,0 +1,375 @@
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    process, thread,
+    time::{Duration, Instant, SystemTime},
+};
+
+use crate::{
+    plugins::{self, PluginSource},
+    usage::directory_size,
+    Error, Result,
+};
+
+/// How often [`PluginWatcher::watch`] looks at the source by default.
+const DEFAULT_INTERVAL: Duration = Duration::from_millis(250);
+
+/// How long the source must stay unchanged before being deployed by default.
+const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// The size and modification time of every file of a source, sorted by path.
+type Snapshot = Vec<(PathBuf, u64, Option<SystemTime>)>;
+
+/// Deploys a plugin into the plugins directory whenever its source changes, for plugin
+/// development. Created by [`RobloxStudio::watch_plugin`](crate::RobloxStudio::watch_plugin).
+///
+/// The source is polled rather than watched through platform-specific APIs. A file source is
+/// deployed as a plugin file, written next to the deployed one and renamed over it, so Roblox
+/// Studio never loads a partially deployed plugin. A directory source is deployed as a plugin
+/// folder, copied next to the deployed one before the two are swapped. That swap isn't
+/// atomic: for a moment, there is no plugin at all. If the new folder can't be moved in place
+/// and the previous one can't be put back, the previous one is left next to it as a hidden
+/// `.old` directory, until the next successful deploy removes it.
+///
+/// Bursts of writes, such as a build writing its output in several steps, are debounced: the
+/// source is only deployed once it stopped changing for [`PluginWatcher::debounce`].
+///
+/// ```no_run
+/// use roblox_install::RobloxStudio;
+///
+/// let studio = RobloxStudio::locate()?;
+///
+/// studio
+///     .watch_plugin("MyPlugin.rbxmx", "build/MyPlugin.rbxmx")?
+///     .watch(|event| {
+///         match event {
+///             Ok(deploy) => println!("{}", deploy),
+///             Err(error) => eprintln!("{}", error),
+///         }
+///
+///         true
+///     });
+/// # Ok::<(), roblox_install::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+#[must_use]
+pub struct PluginWatcher {
+    plugins: PathBuf,
+    name: String,
+    source: PathBuf,
+    interval: Duration,
+    debounce: Duration,
+    seen: Option<Snapshot>,
+    changed_at: Instant,
+    deployed: Option<Snapshot>,
+    deploys: u64,
+}
+
+impl PluginWatcher {
+    pub(crate) fn new(plugins: &Path, name: &str, source: &Path) -> Result<PluginWatcher> {
+        if source.is_dir() {
+            plugins::plugin_path(plugins, name)?;
+        } else {
+            plugins::plugin_file_path(plugins, name)?;
+        }
+
+        Ok(PluginWatcher {
+            plugins: plugins.to_owned(),
+            name: name.to_owned(),
+            source: source.to_owned(),
+            interval: DEFAULT_INTERVAL,
+            debounce: DEFAULT_DEBOUNCE,
+            seen: None,
+            changed_at: Instant::now(),
+            deployed: None,
+            deploys: 0,
+        })
+    }
+
+    /// Sets how often [`PluginWatcher::watch`] looks at the source. Defaults to 250
+    /// milliseconds.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Sets how long the source must stay unchanged before being deployed. Defaults to 500
+    /// milliseconds.
+    pub fn debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+
+    /// Where the plugin is deployed.
+    #[must_use]
+    pub fn plugin_path(&self) -> PathBuf {
+        self.plugins.join(&self.name)
+    }
+
+    /// Looks at the source once, deploying it if it changed since the last deploy and then
+    /// stopped changing for the debounce duration. The first call deploys the source as soon
+    /// as it is stable, even when the plugin is already up to date.
+    ///
+    /// Returns `None` when nothing was deployed, including while the source doesn't exist.
+    /// Fails with [`Error::PluginInstallFailed`] when the source can't be read or the plugin
+    /// can't be written, in which case it is deployed again on the next call.
+    pub fn poll(&mut self) -> Result<Option<PluginDeploy>> {
+        let snapshot = snapshot(&self.source);
+
+        if snapshot != self.seen {
+            self.seen = snapshot.clone();
+            self.changed_at = Instant::now();
+        }
+
+        let snapshot = match snapshot {
+            Some(snapshot) if self.deployed.as_ref() != Some(&snapshot) => snapshot,
+            _ => return Ok(None),
+        };
+
+        if self.changed_at.elapsed() < self.debounce {
+            return Ok(None);
+        }
+
+        let path = if self.source.is_dir() {
+            deploy_directory(&self.plugins, &self.name, &self.source)?
+        } else {
+            plugins::install(
+                &self.plugins,
+                &self.name,
+                PluginSource::File(&self.source),
+                true,
+            )?
+        };
+
+        self.deployed = Some(snapshot);
+        self.deploys += 1;
+
+        Ok(Some(PluginDeploy {
+            size: deployed_size(&path),
+            path,
+            number: self.deploys,
+            time: SystemTime::now(),
+        }))
+    }
+
+    /// Polls the source every [`PluginWatcher::interval`], calling `on_event` with every
+    /// deploy or error, until it returns `false`.
+    pub fn watch<F: FnMut(Result<PluginDeploy>) -> bool>(&mut self, mut on_event: F) {
+        loop {
+            let keep_watching = match self.poll() {
+                Ok(None) => true,
+                Ok(Some(deploy)) => on_event(Ok(deploy)),
+                Err(error) => on_event(Err(error)),
+            };
+
+            if !keep_watching {
+                return;
+            }
+
+            thread::sleep(self.interval);
+        }
+    }
+}
+
+/// A deploy made by a [`PluginWatcher`].
+///
+/// Its [`Display`](fmt::Display) implementation prints a line such as
+/// `deployed MyPlugin.rbxmx (1024 bytes, #3)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PluginDeploy {
+    path: PathBuf,
+    size: u64,
+    number: u64,
+    time: SystemTime,
+}
+
+impl PluginDeploy {
+    /// The path of the deployed plugin.
+    #[must_use]
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The size of the deployed plugin in bytes, including every file of a plugin folder.
+    #[must_use]
+    #[inline]
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// How many times the watcher deployed the plugin, counting this deploy.
+    #[must_use]
+    #[inline]
+    pub fn number(&self) -> u64 {
+        self.number
+    }
+
+    /// When the plugin was deployed.
+    #[must_use]
+    #[inline]
+    pub fn time(&self) -> SystemTime {
+        self.time
+    }
+}
+
+impl fmt::Display for PluginDeploy {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = self.path.file_name().unwrap_or(self.path.as_os_str());
+
+        write!(
+            formatter,
+            "deployed {} ({} bytes, #{})",
+            name.to_string_lossy(),
+            self.size,
+            self.number
+        )
+    }
+}
+
+/// The files of a source, or `None` when it doesn't exist.
+fn snapshot(source: &Path) -> Option<Snapshot> {
+    let metadata = fs::metadata(source).ok()?;
+
+    let mut snapshot = Vec::new();
+
+    if metadata.is_dir() {
+        snapshot_directory(source, Path::new(""), &mut snapshot);
+    } else {
+        snapshot.push((PathBuf::new(), metadata.len(), metadata.modified().ok()));
+    }
+
+    snapshot.sort();
+    Some(snapshot)
+}
+
+fn snapshot_directory(directory: &Path, relative: &Path, snapshot: &mut Snapshot) {
+    let entries = match fs::read_dir(directory) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let relative = relative.join(entry.file_name());
+
+        match entry.metadata() {
+            Ok(metadata) if metadata.is_dir() => {
+                snapshot.push((relative.clone(), 0, None));
+                snapshot_directory(&entry.path(), &relative, snapshot);
+            }
+            Ok(metadata) => snapshot.push((relative, metadata.len(), metadata.modified().ok())),
+            Err(_) => {}
+        }
+    }
+}
+
+fn deployed_size(path: &Path) -> u64 {
+    match fs::metadata(path) {
+        Ok(metadata) if metadata.is_dir() => directory_size(path),
+        Ok(metadata) => metadata.len(),
+        Err(_) => 0,
+    }
+}
+
+/// How many names [`deploy_directory`] tries for the previous deploy before giving up.
+const PREVIOUS_NAME_ATTEMPTS: u32 = 1000;
+
+/// Copies a directory next to the plugin folder, then swaps it with the deployed one.
+fn deploy_directory(plugins: &Path, name: &str, source: &Path) -> Result<PathBuf> {
+    let path = plugins::plugin_path(plugins, name)?;
+    let temporary = plugins.join(format!(".{}.{}.tmp", name, process::id()));
+
+    // a previous deploy stranded by a failed swap is never reused, so it can't be lost
+    let previous = (0..PREVIOUS_NAME_ATTEMPTS)
+        .map(|attempt| plugins.join(format!(".{}.{}.{}.old", name, process::id(), attempt)))
+        .find(|previous| fs::symlink_metadata(previous).is_err())
+        .ok_or_else(|| Error::PluginInstallFailed {
+            path: path.clone(),
+            source: io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                "too many previous deploys were left next to the plugin",
+            ),
+        })?;
+
+    let _ = fs::remove_dir_all(&temporary);
+
+    let moved_aside = fs::create_dir_all(plugins)
+        .and_then(|_| copy_directory(source, &temporary))
+        .and_then(|_| match fs::rename(&path, &previous) {
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(false),
+            renamed => renamed.map(|_| true),
+        });
+
+    let deployed = moved_aside.and_then(|replaced| {
+        fs::rename(&temporary, &path)
+            .map(|_| replaced)
+            .inspect_err(|_| {
+                // put the previous deploy back rather than leaving no plugin at all. If that
+                // fails too, it stays in `previous`.
+                if replaced {
+                    let _ = fs::rename(&previous, &path);
+                }
+            })
+    });
+
+    match deployed {
+        Ok(replaced) => {
+            if replaced {
+                let _ = fs::remove_dir_all(&previous);
+                let _ = fs::remove_file(&previous);
+            }
+
+            remove_stranded_deploys(plugins, name);
+            Ok(path)
+        }
+        Err(source) => {
+            let _ = fs::remove_dir_all(&temporary);
+            Err(Error::PluginInstallFailed { path, source })
+        }
+    }
+}
+
+/// Removes the previous deploys that failed swaps, possibly in other processes, left next to
+/// the plugin folder. They are named `.<name>.<process>.<attempt>.old`.
+fn remove_stranded_deploys(plugins: &Path, name: &str) {
+    let prefix = format!(".{}.", name);
+    let is_number = |text: &str| !text.is_empty() && text.chars().all(|c| c.is_ascii_digit());
+
+    let entries = match fs::read_dir(plugins) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let file_name = entry.file_name();
+
+        let is_stranded = file_name
+            .to_str()
+            .and_then(|file_name| file_name.strip_prefix(&prefix))
+            .and_then(|rest| rest.strip_suffix(".old"))
+            .and_then(|rest| rest.split_once('.'))
+            .is_some_and(|(process, attempt)| is_number(process) && is_number(attempt));
+
+        if is_stranded {
+            let _ = fs::remove_dir_all(entry.path());
+        }
+    }
+}
+
+fn copy_directory(source: &Path, destination: &Path) -> io::Result<()> {
+    fs::create_dir(destination)?;
+
+    for entry in fs::read_dir(source)? {
+        let entry = entry?;
+        let target = destination.join(entry.file_name());
+
+        if entry.file_type()?.is_dir() {
+            copy_directory(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+
+    Ok(())
+}
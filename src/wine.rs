@@ -0,0 +1,67 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const WINE_PREFIX_VARIABLE: &str = "WINEPREFIX";
+
+/// The Wine prefix used when none is given explicitly: `$WINEPREFIX`, or `~/.wine` when the
+/// variable is not defined, the same way Wine itself picks it.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub(crate) fn default_prefix() -> Option<PathBuf> {
+    match env::var_os(WINE_PREFIX_VARIABLE) {
+        Some(prefix) if !prefix.is_empty() => Some(PathBuf::from(prefix)),
+        _ => dirs::home_dir().map(|home| home.join(".wine")),
+    }
+}
+
+/// Every `Roblox` directory in the local application data of the users of a Wine prefix.
+/// Both the current `AppData\Local` layout and the one used by prefixes configured as
+/// Windows XP are searched.
+pub(crate) fn roblox_directories(prefix: &Path) -> Vec<PathBuf> {
+    let users = match fs::read_dir(prefix.join("drive_c").join("users")) {
+        Ok(users) => users,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut user_directories: Vec<PathBuf> = users
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .collect();
+
+    // keep the search order independent from the file system
+    user_directories.sort();
+
+    user_directories
+        .into_iter()
+        .flat_map(|user| {
+            vec![
+                user.join("AppData").join("Local").join("Roblox"),
+                user.join("Local Settings")
+                    .join("Application Data")
+                    .join("Roblox"),
+            ]
+        })
+        .filter(|roblox| roblox.is_dir())
+        .collect()
+}
+
+/// The `Roblox` directory in the local application data of the Wine prefix's user. It is
+/// the first existing one, or the one of the current user when Roblox Studio has not
+/// created it yet.
+pub(crate) fn user_roblox_directory(prefix: &Path) -> Option<PathBuf> {
+    roblox_directories(prefix).into_iter().next().or_else(|| {
+        let user = env::var_os("USER").filter(|user| !user.is_empty())?;
+
+        Some(
+            prefix
+                .join("drive_c")
+                .join("users")
+                .join(user)
+                .join("AppData")
+                .join("Local")
+                .join("Roblox"),
+        )
+    })
+}
@@ -1,12 +1,306 @@
 #![allow(deprecated)]
 
-use std::fs;
+use std::{
+    env, fs,
+    io::{self, BufRead, BufReader, Write},
+    net::TcpListener,
+    path::Path,
+    sync::Mutex,
+    thread,
+    time::{Duration, SystemTime},
+};
 
-use roblox_install::RobloxStudio;
+use roblox_install::{
+    BundleInfo, Error, Installer, InstanceClass, IntegrityIssue, LocateStep, MemoryRegistry,
+    Platform, PluginChange, PluginIssue, PluginKind, PluginManifest, PluginModel, RobloxStudio,
+    StudioLocator, StudioSource, StudioVersion, VersionDirectory, VersionResource,
+    VersionSelection, WineRegistry,
+};
+
+// Tests that set or read `ROBLOX_STUDIO_PATH` must hold this lock, as the environment is
+// shared by every test thread.
+static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+fn create_version(versions: &Path, name: &str) {
+    let version = versions.join(name);
+    fs::create_dir_all(version.join("content")).unwrap();
+    fs::create_dir_all(version.join("BuiltInPlugins")).unwrap();
+    fs::write(version.join("RobloxStudioBeta.exe"), b"").unwrap();
+}
+
+fn set_version_modified(versions: &Path, name: &str, seconds_ago: u64) {
+    let application = fs::File::options()
+        .write(true)
+        .open(versions.join(name).join("RobloxStudioBeta.exe"))
+        .unwrap();
+
+    application
+        .set_modified(SystemTime::now() - Duration::from_secs(seconds_ago))
+        .unwrap();
+}
+
+fn utf16(text: &str) -> Vec<u8> {
+    text.encode_utf16()
+        .chain(Some(0))
+        .flat_map(u16::to_le_bytes)
+        .collect()
+}
+
+fn pad(bytes: &mut Vec<u8>) {
+    bytes.resize((bytes.len() + 3) & !3, 0);
+}
+
+/// Builds a binary property list holding a dictionary of strings.
+fn binary_plist(entries: &[(&str, &str)]) -> Vec<u8> {
+    let mut plist = b"bplist00".to_vec();
+    let mut offsets = vec![plist.len()];
+    let count = entries.len() as u8;
+
+    plist.push(0xd0 | count);
+    plist.extend(1..=count);
+    plist.extend(count + 1..=count * 2);
+
+    let keys = entries.iter().map(|(key, _)| key);
+    let values = entries.iter().map(|(_, value)| value);
+
+    for string in keys.chain(values) {
+        offsets.push(plist.len());
+
+        if string.len() < 15 {
+            plist.push(0x50 | string.len() as u8);
+        } else {
+            plist.extend_from_slice(&[0x5f, 0x10, string.len() as u8]);
+        }
+
+        plist.extend_from_slice(string.as_bytes());
+    }
+
+    let offset_table = plist.len() as u64;
+    plist.extend(offsets.iter().map(|offset| *offset as u8));
+    plist.extend_from_slice(&[0, 0, 0, 0, 0, 0, 1, 1]);
+    plist.extend_from_slice(&(offsets.len() as u64).to_be_bytes());
+    plist.extend_from_slice(&0u64.to_be_bytes());
+    plist.extend_from_slice(&offset_table.to_be_bytes());
+    plist
+}
+
+/// Builds a node of a `VS_VERSIONINFO` resource.
+fn version_block(key: &str, value: &[u8], is_text: bool, children: &[Vec<u8>]) -> Vec<u8> {
+    let value_length = if is_text { value.len() / 2 } else { value.len() };
+    let mut block = vec![0; 4];
+    block.extend_from_slice(&u16::from(is_text).to_le_bytes());
+    block.extend(utf16(key));
+    pad(&mut block);
+    block.extend_from_slice(value);
+
+    for child in children {
+        pad(&mut block);
+        block.extend_from_slice(child);
+    }
+
+    let length = block.len() as u16;
+    block[0..2].copy_from_slice(&length.to_le_bytes());
+    block[2..4].copy_from_slice(&(value_length as u16).to_le_bytes());
+    block
+}
+
+/// Writes a 64 bit PE executable with nothing but a version resource.
+fn write_executable(path: &Path, file_version: &str, numbers: [u16; 4]) {
+    let mut fixed_file_info = Vec::new();
+
+    for field in [
+        0xFEEF_04BD,
+        0x0001_0000,
+        u32::from(numbers[0]) << 16 | u32::from(numbers[1]),
+        u32::from(numbers[2]) << 16 | u32::from(numbers[3]),
+        u32::from(numbers[0]) << 16 | u32::from(numbers[1]),
+        u32::from(numbers[2]) << 16 | u32::from(numbers[3]),
+    ] {
+        fixed_file_info.extend_from_slice(&u32::to_le_bytes(field));
+    }
+
+    fixed_file_info.resize(52, 0);
+
+    let strings = version_block(
+        "040904b0",
+        &[],
+        true,
+        &[
+            version_block("CompanyName", &utf16("Roblox Corporation"), true, &[]),
+            version_block("FileVersion", &utf16(file_version), true, &[]),
+            version_block("ProductVersion", &utf16(file_version), true, &[]),
+        ],
+    );
+
+    let version_info = version_block(
+        "VS_VERSION_INFO",
+        &fixed_file_info,
+        false,
+        &[version_block("StringFileInfo", &[], true, &[strings])],
+    );
+
+    // resource types, then names, then languages, then the data entry and the data itself
+    let mut resources = Vec::new();
+
+    for (id, target) in [(16, 0x8000_0018u32), (1, 0x8000_0030), (0x409, 0x48)] {
+        resources.extend_from_slice(&[0; 14]);
+        resources.extend_from_slice(&1u16.to_le_bytes());
+        resources.extend_from_slice(&u32::to_le_bytes(id));
+        resources.extend_from_slice(&target.to_le_bytes());
+    }
+
+    resources.extend_from_slice(&u32::to_le_bytes(0x1000 + 0x58));
+    resources.extend_from_slice(&(version_info.len() as u32).to_le_bytes());
+    resources.extend_from_slice(&[0; 8]);
+    resources.extend(version_info);
+
+    let mut executable = vec![0; 0x200];
+    executable[0..2].copy_from_slice(b"MZ");
+    executable[0x3c..0x40].copy_from_slice(&0x40u32.to_le_bytes());
+    executable[0x40..0x44].copy_from_slice(b"PE\0\0");
+    executable[0x44..0x46].copy_from_slice(&0x8664u16.to_le_bytes());
+    executable[0x46..0x48].copy_from_slice(&1u16.to_le_bytes());
+    executable[0x54..0x56].copy_from_slice(&240u16.to_le_bytes());
+
+    let optional_header = 0x58;
+    executable[optional_header..optional_header + 2].copy_from_slice(&0x20bu16.to_le_bytes());
+    executable[optional_header + 108..optional_header + 112].copy_from_slice(&16u32.to_le_bytes());
+    executable[optional_header + 128..optional_header + 132]
+        .copy_from_slice(&0x1000u32.to_le_bytes());
+    executable[optional_header + 132..optional_header + 136]
+        .copy_from_slice(&(resources.len() as u32).to_le_bytes());
+
+    let section = optional_header + 240;
+    let resources_size = (resources.len() as u32).to_le_bytes();
+    executable[section..section + 5].copy_from_slice(b".rsrc");
+    executable[section + 8..section + 12].copy_from_slice(&resources_size);
+    executable[section + 12..section + 16].copy_from_slice(&0x1000u32.to_le_bytes());
+    executable[section + 16..section + 20].copy_from_slice(&resources_size);
+    executable[section + 20..section + 24].copy_from_slice(&0x200u32.to_le_bytes());
+
+    executable.extend(resources);
+    fs::write(path, executable).unwrap();
+}
+
+/// The paths and contents of the files in a synthetic package.
+type PackageFiles<'a> = [(&'a str, &'a [u8])];
+
+fn package_zip(files: &PackageFiles) -> Vec<u8> {
+    let mut zip = zip::ZipWriter::new(io::Cursor::new(Vec::new()));
+
+    for (name, contents) in files {
+        zip.start_file(*name, zip::write::SimpleFileOptions::default())
+            .unwrap();
+        zip.write_all(contents).unwrap();
+    }
+
+    zip.finish().unwrap().into_inner()
+}
+
+/// Writes synthetic packages and their `rbxPkgManifest.txt` in a mirror directory, with the
+/// version as a prefix like on the Roblox deployment servers.
+fn write_mirror(mirror: &Path, version: &str, packages: &[(&str, &PackageFiles)]) {
+    let mut manifest = "v0\n".to_owned();
+
+    for (name, files) in packages {
+        let data = package_zip(files);
+        let size: usize = files.iter().map(|(_, contents)| contents.len()).sum();
+
+        manifest += &format!(
+            "{}\n{:x}\n{}\n{}\n",
+            name,
+            md5::compute(&data),
+            data.len(),
+            size
+        );
+        fs::write(mirror.join(format!("{}-{}", version, name)), data).unwrap();
+    }
+
+    fs::write(
+        mirror.join(format!("{}-rbxPkgManifest.txt", version)),
+        manifest,
+    )
+    .unwrap();
+}
+
+/// Serves the files of a directory over HTTP on a local port, and returns the URL.
+fn serve_directory(directory: &Path) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/mirror/", listener.local_addr().unwrap());
+    let directory = directory.to_owned();
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(&stream);
+            let mut request = String::new();
+            reader.read_line(&mut request).unwrap();
+
+            // the headers are read so the connection isn't reset while the client writes them
+            let mut header = String::new();
+            while reader.read_line(&mut header).unwrap() > 2 {
+                header.clear();
+            }
+
+            let name = request.split_whitespace().nth(1).unwrap_or_default();
+            let file = name
+                .strip_prefix("/mirror/")
+                .and_then(|name| fs::read(directory.join(name)).ok());
+
+            let _ = match file {
+                Some(data) => write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n",
+                    data.len()
+                )
+                .and_then(|_| stream.write_all(&data)),
+                None => stream.write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n"),
+            };
+        }
+    });
+
+    url
+}
+
+#[test]
+fn test_locate_all_from_env() {
+    let roblox = tempfile::tempdir().unwrap();
+    let versions = roblox.path().join("Versions");
+    create_version(&versions, "version-a");
+    create_version(&versions, "version-b");
+    fs::create_dir_all(versions.join("version-incomplete")).unwrap();
+
+    let locator = StudioLocator::empty()
+        .platform(Platform::Windows)
+        .source(StudioSource::EnvironmentVariable);
+
+    let _guard = ENV_LOCK.lock().unwrap();
+    env::set_var("ROBLOX_STUDIO_PATH", roblox.path());
+    let mut installs = locator.locate_all();
+    env::remove_var("ROBLOX_STUDIO_PATH");
+
+    installs.sort_by(|a, b| a.application_path().cmp(b.application_path()));
+
+    assert_eq!(installs.len(), 2);
+    assert_eq!(
+        installs[0].application_path(),
+        versions.join("version-a").join("RobloxStudioBeta.exe")
+    );
+    assert_eq!(
+        installs[1].application_path(),
+        versions.join("version-b").join("RobloxStudioBeta.exe")
+    );
+
+    for install in &installs {
+        assert_eq!(install.source(), &StudioSource::EnvironmentVariable);
+        assert_eq!(install.plugins_path(), roblox.path().join("Plugins"));
+    }
+}
 
 #[cfg(target_os = "windows")]
 #[test]
 fn test_windows() {
+    let _guard = ENV_LOCK.lock().unwrap();
     let studio = RobloxStudio::locate().unwrap();
 
     assert!(studio.root_path().to_string_lossy().contains("Roblox"));
@@ -32,6 +326,7 @@ fn test_windows() {
 #[cfg(target_os = "macos")]
 #[test]
 fn test_macos() {
+    let _guard = ENV_LOCK.lock().unwrap();
     let studio = RobloxStudio::locate().unwrap();
 
     assert!(studio
@@ -56,3 +351,1781 @@ fn test_macos() {
     assert!(meta.is_dir());
     assert!(content.to_string_lossy().contains("content"));
 }
+
+#[test]
+fn test_locate_newest_version() {
+    let roblox = tempfile::tempdir().unwrap();
+    let versions = roblox.path().join("Versions");
+
+    for (name, seconds_ago) in &[("version-a", 300), ("version-b", 100), ("version-c", 200)] {
+        create_version(&versions, name);
+        set_version_modified(&versions, name, *seconds_ago);
+    }
+
+    let locator = StudioLocator::empty()
+        .platform(Platform::Windows)
+        .directory(roblox.path())
+        .version_selection(VersionSelection::NewestExecutable);
+
+    let newest = locator.locate().unwrap();
+    assert_eq!(newest.content_path(), versions.join("version-b").join("content"));
+
+    let all = locator.locate_all();
+    let all_contents: Vec<_> = all.iter().map(RobloxStudio::content_path).collect();
+    assert_eq!(
+        all_contents,
+        vec![
+            versions.join("version-b").join("content"),
+            versions.join("version-c").join("content"),
+            versions.join("version-a").join("content"),
+        ]
+    );
+}
+
+#[test]
+fn test_locator_directory() {
+    let roblox = tempfile::tempdir().unwrap();
+    let versions = roblox.path().join("Versions");
+    create_version(&versions, "version-a");
+
+    let studio = StudioLocator::empty()
+        .platform(Platform::Windows)
+        .directory(roblox.path())
+        .locate()
+        .unwrap();
+
+    assert_eq!(
+        studio.source(),
+        &StudioSource::Directory(roblox.path().to_owned())
+    );
+    assert_eq!(studio.content_path(), versions.join("version-a").join("content"));
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+#[test]
+fn test_locator_sources() {
+    let first = tempfile::tempdir().unwrap();
+    let second = tempfile::tempdir().unwrap();
+    create_version(&first.path().join("Versions"), "version-a");
+    create_version(&second.path().join("Versions"), "version-b");
+
+    let locator = StudioLocator::empty()
+        .sources(vec![
+            StudioSource::EnvironmentVariable,
+            StudioSource::Registry,
+            StudioSource::DefaultLocation,
+        ])
+        .without_source(&StudioSource::EnvironmentVariable)
+        .directory(first.path())
+        .directory(second.path());
+
+    assert_eq!(
+        locator.search_sources(),
+        &[
+            StudioSource::Registry,
+            StudioSource::DefaultLocation,
+            StudioSource::Directory(first.path().to_owned()),
+            StudioSource::Directory(second.path().to_owned()),
+        ]
+    );
+
+    // there is no registry nor default location here, so the first directory is used
+    let studio = locator.locate().unwrap();
+    assert_eq!(
+        studio.source(),
+        &StudioSource::Directory(first.path().to_owned())
+    );
+
+    // adding a source again moves it to the end
+    let studio = locator.directory(first.path()).locate().unwrap();
+    assert_eq!(
+        studio.source(),
+        &StudioSource::Directory(second.path().to_owned())
+    );
+
+    let missing = first.path().join("missing");
+    assert!(StudioLocator::empty().directory(missing).locate().is_err());
+}
+
+#[test]
+fn test_locate_wine_prefix() {
+    let prefix = tempfile::tempdir().unwrap();
+    let roblox = prefix
+        .path()
+        .join("drive_c")
+        .join("users")
+        .join("tester")
+        .join("AppData")
+        .join("Local")
+        .join("Roblox");
+    create_version(&roblox.join("Versions"), "version-a");
+    fs::create_dir_all(prefix.path().join("drive_c").join("users").join("Public")).unwrap();
+
+    let studio = StudioLocator::empty()
+        .wine_prefix(prefix.path())
+        .locate()
+        .unwrap();
+
+    assert_eq!(
+        studio.source(),
+        &StudioSource::WinePrefix(prefix.path().to_owned())
+    );
+    assert_eq!(
+        studio.application_path(),
+        roblox.join("Versions").join("version-a").join("RobloxStudioBeta.exe")
+    );
+    assert_eq!(
+        studio.content_path(),
+        roblox.join("Versions").join("version-a").join("content")
+    );
+    assert_eq!(studio.plugins_path(), roblox.join("Plugins"));
+
+    let empty_prefix = tempfile::tempdir().unwrap();
+    assert!(StudioLocator::empty()
+        .wine_prefix(empty_prefix.path())
+        .locate()
+        .is_err());
+}
+
+#[test]
+fn test_locate_vinegar() {
+    let vinegar = tempfile::tempdir().unwrap();
+    let data = vinegar.path().join("data");
+    let config = vinegar.path().join("config.toml");
+
+    let versions = data.join("versions");
+    create_version(&versions, "version-a");
+    create_version(&versions, "version-b");
+    set_version_modified(&versions, "version-a", 200);
+    set_version_modified(&versions, "version-b", 100);
+
+    let roblox = data
+        .join("prefixes")
+        .join("studio")
+        .join("drive_c")
+        .join("users")
+        .join("tester")
+        .join("AppData")
+        .join("Local")
+        .join("Roblox");
+    fs::create_dir_all(&roblox).unwrap();
+
+    let locator = StudioLocator::empty().vinegar(&data, &config);
+
+    // without a state nor a configuration, the newest version is used
+    let studio = locator.locate().unwrap();
+    assert_eq!(studio.content_path(), versions.join("version-b").join("content"));
+    assert_eq!(studio.plugins_path(), roblox.join("Plugins"));
+    assert_eq!(
+        studio.source(),
+        &StudioSource::Vinegar {
+            data: data.clone(),
+            config: config.clone(),
+        }
+    );
+
+    fs::write(
+        data.join("state.toml"),
+        "[studio]\nversion = \"version-a\"\npackages = []\n",
+    )
+    .unwrap();
+
+    let studio = locator.locate().unwrap();
+    assert_eq!(studio.content_path(), versions.join("version-a").join("content"));
+    assert_eq!(locator.locate_all().len(), 1);
+
+    fs::write(&config, "[studio]\nforced_version = \"version-b\"\n").unwrap();
+
+    let studio = locator.locate().unwrap();
+    assert_eq!(
+        studio.application_path(),
+        versions.join("version-b").join("RobloxStudioBeta.exe")
+    );
+
+    fs::write(&config, "[studio\n").unwrap();
+    assert!(matches!(
+        locator.locate(),
+        Err(Error::VinegarConfigError(_))
+    ));
+}
+
+#[test]
+fn test_locate_wine_registry() {
+    let prefix = tempfile::tempdir().unwrap();
+    let user = prefix.path().join("drive_c").join("users").join("tester");
+    let versions = user
+        .join("AppData")
+        .join("Local")
+        .join("Roblox")
+        .join("Versions");
+    create_version(&versions, "version-a");
+    create_version(&versions, "version-b");
+    set_version_modified(&versions, "version-a", 200);
+    set_version_modified(&versions, "version-b", 100);
+
+    fs::write(
+        prefix.path().join("user.reg"),
+        "WINE REGISTRY Version 2\n\
+         ;; All keys relative to \\\\User\\\\S-1-5-21-0-0-0-1000\n\
+         \n\
+         #arch=win64\n\
+         \n\
+         [Software\\\\Roblox\\\\RobloxStudio] 1700000000\n\
+         #time=1da1b2c3d4e5f60\n\
+         \"Num\"=dword:00000001\n\
+         \"contentfolder\"=\"C:\\\\users\\\\tester\\\\AppData\\\\Local\\\\Roblox\\\\Versions\\\\version-a\\\\content\"\n",
+    )
+    .unwrap();
+
+    let locator = StudioLocator::empty().wine_prefix(prefix.path());
+
+    let studio = locator
+        .clone()
+        .version_selection(VersionSelection::RegistryContentFolder)
+        .locate()
+        .unwrap();
+    assert_eq!(studio.content_path(), versions.join("version-a").join("content"));
+
+    let studio = locator.locate().unwrap();
+    assert_eq!(studio.content_path(), versions.join("version-b").join("content"));
+
+    // the registry can point outside of the scanned `Versions` directories
+    let elsewhere = prefix.path().join("drive_c").join("Roblox").join("Versions");
+    create_version(&elsewhere, "version-c");
+    fs::write(
+        prefix.path().join("user.reg"),
+        "[Software\\\\Roblox\\\\RobloxStudio]\n\
+         \"ContentFolder\"=str(2):\"C:\\\\Roblox\\\\Versions\\\\version-c\\\\content\"\n",
+    )
+    .unwrap();
+
+    let studio = locator
+        .clone()
+        .version_selection(VersionSelection::RegistryContentFolder)
+        .locate()
+        .unwrap();
+    assert_eq!(studio.content_path(), elsewhere.join("version-c").join("content"));
+    assert_eq!(
+        studio.plugins_path(),
+        prefix.path().join("drive_c").join("Roblox").join("Plugins")
+    );
+    assert_eq!(locator.locate_all().len(), 3);
+}
+
+#[test]
+fn test_locate_from_registry() {
+    let key = r"Software\Roblox\RobloxStudio";
+
+    assert!(matches!(
+        RobloxStudio::locate_from_registry(&MemoryRegistry::new()),
+        Err(Error::RegistryError(_))
+    ));
+
+    let registry = MemoryRegistry::new().with_value(key, "ContentFolder", "/content");
+    assert!(matches!(
+        RobloxStudio::locate_from_registry(&registry),
+        Err(Error::MalformedRegistry)
+    ));
+
+    let roblox = Path::new("/home/roblox/Roblox");
+    let content = roblox.join("Versions").join("version-a").join("content");
+    let registry = MemoryRegistry::new().with_value(
+        &key.to_uppercase(),
+        "contentfolder",
+        content.to_string_lossy(),
+    );
+
+    let studio = RobloxStudio::locate_from_registry(&registry).unwrap();
+    assert_eq!(studio.source(), &StudioSource::Registry);
+    assert_eq!(studio.content_path(), content);
+    assert_eq!(studio.plugins_path(), roblox.join("Plugins"));
+    assert_eq!(
+        studio.application_path(),
+        roblox
+            .join("Versions")
+            .join("version-a")
+            .join("RobloxStudioBeta.exe")
+    );
+}
+
+#[test]
+fn test_wine_registry() {
+    let prefix = Path::new("/home/roblox/.wine");
+    let registry = WineRegistry::parse(
+        prefix,
+        "WINE REGISTRY Version 2\n\
+         [Software\\\\Roblox\\\\RobloxStudio] 1700000000\n\
+         \"ContentFolder\"=\"D:\\\\Roblox\\\\\\x00e9t\\xe9\\\\Versions\\\\version-a\\\\content\"\n",
+    );
+
+    let studio = RobloxStudio::locate_from_registry(&registry).unwrap();
+    assert_eq!(
+        studio.content_path(),
+        prefix
+            .join("dosdevices")
+            .join("d:")
+            .join("Roblox")
+            .join("été")
+            .join("Versions")
+            .join("version-a")
+            .join("content")
+    );
+
+    let registry = WineRegistry::parse(prefix, "[Software\\\\Roblox]\n@=\"value\"\n");
+    assert!(matches!(
+        RobloxStudio::locate_from_registry(&registry),
+        Err(Error::RegistryError(_))
+    ));
+}
+
+#[test]
+fn test_locate_in_windows() {
+    let roblox = tempfile::tempdir().unwrap();
+    let versions = roblox.path().join("Versions");
+    create_version(&versions, "version-a");
+
+    for root in &[roblox.path().to_owned(), versions.join("version-a")] {
+        let studio = RobloxStudio::locate_in(root, Platform::Windows).unwrap();
+
+        assert_eq!(studio.source(), &StudioSource::Directory(root.to_owned()));
+        assert_eq!(
+            studio.application_path(),
+            versions.join("version-a").join("RobloxStudioBeta.exe")
+        );
+        assert_eq!(
+            studio.built_in_plugins_path(),
+            versions.join("version-a").join("BuiltInPlugins")
+        );
+        assert_eq!(studio.plugins_path(), roblox.path().join("Plugins"));
+    }
+
+    assert!(RobloxStudio::locate_in(roblox.path().join("missing"), Platform::Windows).is_err());
+}
+
+#[test]
+fn test_locate_errors() {
+    let roblox = tempfile::tempdir().unwrap();
+
+    match RobloxStudio::locate_in(roblox.path(), Platform::Windows) {
+        Err(Error::StudioDirectoryNotFound(path)) => assert_eq!(path, roblox.path()),
+        other => panic!("expected StudioDirectoryNotFound, got {:?}", other),
+    }
+
+    let versions = roblox.path().join("Versions");
+    fs::create_dir_all(versions.join("version-incomplete").join("content")).unwrap();
+
+    match RobloxStudio::locate_in(roblox.path(), Platform::Windows) {
+        Err(Error::NoVersionFound(path)) => assert_eq!(path, versions),
+        other => panic!("expected NoVersionFound, got {:?}", other),
+    }
+
+    let error = RobloxStudio::locate_in(roblox.path(), Platform::Windows).unwrap_err();
+    assert!(error.to_string().contains(&versions.display().to_string()));
+
+    let missing = roblox.path().join("missing");
+
+    match RobloxStudio::locate_in(&missing, Platform::Windows) {
+        Err(Error::StudioDirectoryNotFound(path)) => assert_eq!(path, missing),
+        other => panic!("expected StudioDirectoryNotFound, got {:?}", other),
+    }
+
+    let file = tempfile::tempdir().unwrap();
+    fs::write(file.path().join("Versions"), "").unwrap();
+
+    match RobloxStudio::locate_in(file.path(), Platform::Windows) {
+        Err(Error::VersionsDirectoryUnreadable { path, .. }) => {
+            assert_eq!(path, file.path().join("Versions"))
+        }
+        other => panic!("expected VersionsDirectoryUnreadable, got {:?}", other),
+    }
+
+    // a relative version directory has nothing above it to hold the user plugins
+    let relative = tempfile::Builder::new().tempdir_in(".").unwrap();
+    let version = Path::new(relative.path().file_name().unwrap());
+    fs::create_dir_all(version.join("content")).unwrap();
+
+    match RobloxStudio::locate_in(version, Platform::Windows) {
+        Err(Error::RobloxDirectoryNotFound(path)) => assert_eq!(path, version),
+        other => panic!("expected RobloxDirectoryNotFound, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_locate_in_macos() {
+    let applications = tempfile::tempdir().unwrap();
+    let bundle = applications.path().join("RobloxStudio.app");
+    let contents = bundle.join("Contents");
+    fs::create_dir_all(contents.join("MacOS")).unwrap();
+    fs::create_dir_all(contents.join("Resources").join("content")).unwrap();
+    fs::write(contents.join("MacOS").join("RobloxStudio"), b"").unwrap();
+
+    let studio = RobloxStudio::locate_in(&bundle, Platform::MacOs).unwrap();
+
+    assert_eq!(studio.root_path(), bundle);
+    assert_eq!(
+        studio.application_path(),
+        contents.join("MacOS").join("RobloxStudio")
+    );
+    assert_eq!(
+        studio.content_path(),
+        contents.join("Resources").join("content")
+    );
+    assert_eq!(
+        studio.built_in_plugins_path(),
+        contents.join("Resources").join("BuiltInPlugins")
+    );
+    assert!(studio
+        .plugins_path()
+        .ends_with(Path::new("Roblox").join("Plugins")));
+}
+
+#[test]
+fn test_locate_with_report() {
+    let missing = tempfile::tempdir().unwrap();
+    let roblox = tempfile::tempdir().unwrap();
+    let versions = roblox.path().join("Versions");
+    create_version(&versions, "version-complete");
+    fs::create_dir_all(versions.join("version-incomplete").join("content")).unwrap();
+
+    let (studio, report) = StudioLocator::empty()
+        .platform(Platform::Windows)
+        .wine_prefix(missing.path())
+        .directory(roblox.path())
+        .locate_with_report();
+
+    let application = versions
+        .join("version-complete")
+        .join("RobloxStudioBeta.exe");
+    assert_eq!(studio.unwrap().application_path(), application);
+
+    let steps = report.steps();
+    assert_eq!(
+        steps.first(),
+        Some(&LocateStep::SearchSource(StudioSource::WinePrefix(
+            missing.path().to_owned()
+        )))
+    );
+    assert!(steps.contains(&LocateStep::MissingFile(missing.path().join("user.reg"))));
+    assert!(steps.iter().any(|step| matches!(
+        step,
+        LocateStep::SourceFailed {
+            source: StudioSource::WinePrefix(_),
+            ..
+        }
+    )));
+    assert!(steps.contains(&LocateStep::CandidateDirectory(roblox.path().to_owned())));
+    assert!(steps.contains(&LocateStep::MissingFile(
+        versions
+            .join("version-incomplete")
+            .join("RobloxStudioBeta.exe")
+    )));
+    assert!(steps.contains(&LocateStep::CandidateFound(application.clone())));
+    assert_eq!(steps.last(), Some(&LocateStep::Selected(application.clone())));
+
+    let text = report.to_string();
+    assert!(text.starts_with(&format!(
+        "Searching the Wine prefix `{}`\n",
+        missing.path().display()
+    )));
+    assert!(text.contains(&format!("\n  Selected `{}`\n", application.display())));
+}
+
+#[test]
+fn test_version() {
+    let roblox = tempfile::tempdir().unwrap();
+    let versions = roblox.path().join("Versions");
+    create_version(&versions, "version-1a2b3c4d");
+    write_executable(
+        &versions.join("version-1a2b3c4d").join("RobloxStudioBeta.exe"),
+        "0, 612, 0, 6120532",
+        [0, 612, 0, 25940],
+    );
+
+    let studio = RobloxStudio::locate_in(roblox.path(), Platform::Windows).unwrap();
+    let version = studio.version().unwrap();
+
+    assert_eq!(version.hash(), Some("1a2b3c4d"));
+    assert_eq!(version.folder_name().as_deref(), Some("version-1a2b3c4d"));
+    assert_eq!(version.numbers(), Some(&[0, 612, 0, 6120532][..]));
+    assert_eq!(version.to_string(), "0.612.0.6120532 (version-1a2b3c4d)");
+
+    assert!(version > "0.600".parse().unwrap());
+    assert!(version < "0.612.0.6120533".parse().unwrap());
+    assert_eq!(version.is_at_least(&"0.612.0.6120532".parse().unwrap()), Some(true));
+    assert_eq!(version.is_at_least(&"0.613".parse().unwrap()), Some(false));
+    assert_eq!(version, "0.612.0.6120532".parse().unwrap());
+
+    // hashes aren't ordered, so a version without a dotted version can't be compared
+    let hash_only: StudioVersion = "version-1a2b3c4d".parse().unwrap();
+    assert_eq!(version.partial_cmp(&hash_only), None);
+    assert_eq!(version.is_at_least(&hash_only), None);
+    assert_ne!(version, hash_only);
+    assert_eq!(hash_only, "version-1a2b3c4d".parse().unwrap());
+    assert_ne!(hash_only, "version-5e6f7a8b".parse().unwrap());
+    assert!(matches!(
+        "version 612".parse::<StudioVersion>(),
+        Err(Error::InvalidVersion(_))
+    ));
+
+    // a truncated executable only keeps its headers
+    let application = studio.application_path();
+    let mut executable = fs::read(application).unwrap();
+    executable.truncate(0x100);
+    fs::write(application, &executable).unwrap();
+
+    // the hash is still known
+    let version = studio.version().unwrap();
+    assert_eq!(version.hash(), Some("1a2b3c4d"));
+    assert_eq!(version.numbers(), None);
+
+    // without it, nothing is
+    let unnamed = roblox.path().join("Studio");
+    fs::create_dir_all(unnamed.join("content")).unwrap();
+    fs::write(unnamed.join("RobloxStudioBeta.exe"), executable).unwrap();
+    let studio = RobloxStudio::locate_in(&unnamed, Platform::Windows).unwrap();
+
+    match studio.version() {
+        Err(Error::CorruptExecutable { path, .. }) => {
+            assert_eq!(path, unnamed.join("RobloxStudioBeta.exe"))
+        }
+        other => panic!("expected CorruptExecutable, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_version_macos() {
+    let applications = tempfile::tempdir().unwrap();
+    let bundle = applications.path().join("RobloxStudio.app");
+    let contents = bundle.join("Contents");
+    fs::create_dir_all(contents.join("MacOS")).unwrap();
+    fs::write(contents.join("MacOS").join("RobloxStudio"), b"").unwrap();
+    fs::write(
+        contents.join("Info.plist"),
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>CFBundleExecutable</key>
+    <string>RobloxStudio</string>
+    <key>CFBundleShortVersionString</key>
+    <string>0.612.0.6120532</string>
+</dict>
+</plist>
+"#,
+    )
+    .unwrap();
+
+    let version = RobloxStudio::locate_in(&bundle, Platform::MacOs)
+        .unwrap()
+        .version()
+        .unwrap();
+
+    assert_eq!(version.hash(), None);
+    assert_eq!(version.to_string(), "0.612.0.6120532");
+}
+
+#[test]
+fn test_bundle_info() {
+    let info = BundleInfo::parse(&binary_plist(&[
+        ("CFBundleExecutable", "RobloxStudio"),
+        ("CFBundleIdentifier", "com.roblox.RobloxStudio"),
+        ("CFBundleShortVersionString", "0.612.0.6120532"),
+    ]))
+    .unwrap();
+
+    assert_eq!(info.executable(), Some("RobloxStudio"));
+    assert_eq!(info.identifier(), Some("com.roblox.RobloxStudio"));
+    assert_eq!(info.short_version(), Some("0.612.0.6120532"));
+    assert_eq!(info.version(), None);
+
+    let info = BundleInfo::parse(
+        br#"<?xml version="1.0" encoding="UTF-8"?>
+<plist version="1.0">
+<dict>
+    <!-- <key>CFBundleExecutable</key><string>Commented</string> -->
+    <key>CFBundleDocumentTypes</key>
+    <array>
+        <dict>
+            <key>CFBundleTypeExtensions</key>
+            <array><string>rbxl</string><string>rbxlx</string></array>
+            <key>LSIsAppleDefaultForType</key>
+            <true/>
+        </dict>
+    </array>
+    <key>CFBundleExecutable</key>
+    <string>Roblox &amp; Studio</string>
+    <key>CFBundleVersion</key>
+    <string>6120532</string>
+    <key>LSMinimumSystemVersion</key>
+    <integer>11</integer>
+</dict>
+</plist>
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(info.executable(), Some("Roblox & Studio"));
+    assert_eq!(info.version(), Some("6120532"));
+
+    assert!(BundleInfo::parse(b"bplist00\xd1\x01").is_err());
+    assert!(BundleInfo::parse(b"<plist><dict><key>A</key></plist>").is_err());
+}
+
+#[test]
+fn test_locate_in_macos_renamed_executable() {
+    let applications = tempfile::tempdir().unwrap();
+    let bundle = applications.path().join("Roblox Studio.app");
+    let contents = bundle.join("Contents");
+    fs::create_dir_all(contents.join("MacOS")).unwrap();
+    fs::write(contents.join("MacOS").join("RobloxStudioBeta"), b"").unwrap();
+    fs::write(
+        contents.join("Info.plist"),
+        binary_plist(&[
+            ("CFBundleExecutable", "RobloxStudioBeta"),
+            ("CFBundleIdentifier", "com.roblox.RobloxStudio"),
+            ("CFBundleShortVersionString", "0.612.0.6120532"),
+        ]),
+    )
+    .unwrap();
+
+    let studio = RobloxStudio::locate_in(&bundle, Platform::MacOs).unwrap();
+
+    assert_eq!(
+        studio.application_path(),
+        contents.join("MacOS").join("RobloxStudioBeta")
+    );
+    assert_eq!(studio.version().unwrap().to_string(), "0.612.0.6120532");
+
+    fs::write(contents.join("Info.plist"), b"bplist00").unwrap();
+
+    match RobloxStudio::locate_in(&bundle, Platform::MacOs) {
+        Err(Error::BundleInfoUnreadable { path, .. }) => {
+            assert_eq!(path, contents.join("Info.plist"))
+        }
+        other => panic!("expected BundleInfoUnreadable, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_locate_macos_applications() {
+    let applications = tempfile::tempdir().unwrap();
+
+    let create_bundle = |name: &str, identifier: &str| {
+        let contents = applications.path().join(name).join("Contents");
+        fs::create_dir_all(contents.join("MacOS")).unwrap();
+        fs::write(contents.join("MacOS").join("RobloxStudio"), b"").unwrap();
+        fs::write(
+            contents.join("Info.plist"),
+            binary_plist(&[
+                ("CFBundleExecutable", "RobloxStudio"),
+                ("CFBundleIdentifier", identifier),
+            ]),
+        )
+        .unwrap();
+    };
+
+    match RobloxStudio::locate_in(applications.path(), Platform::MacOs) {
+        Err(Error::NoVersionFound(path)) => assert_eq!(path, applications.path()),
+        other => panic!("expected NoVersionFound, got {:?}", other),
+    }
+
+    create_bundle("RobloxStudio.app", "com.example.NotRobloxStudio");
+    create_bundle("Roblox Studio.app", "com.roblox.RobloxStudio");
+
+    let studio = RobloxStudio::locate_in(applications.path(), Platform::MacOs).unwrap();
+    assert_eq!(studio.root_path(), applications.path().join("Roblox Studio.app"));
+
+    let installs = StudioLocator::empty()
+        .platform(Platform::MacOs)
+        .directory(applications.path())
+        .locate_all();
+
+    assert_eq!(installs.len(), 1);
+
+    // a bundle given explicitly is used whatever its identifier
+    let bundle = applications.path().join("RobloxStudio.app");
+    let studio = RobloxStudio::locate_in(&bundle, Platform::MacOs).unwrap();
+    assert_eq!(studio.root_path(), bundle);
+}
+
+#[test]
+fn test_version_resource() {
+    let version = tempfile::tempdir().unwrap();
+    let application = version.path().join("RobloxStudioBeta.exe");
+    write_executable(&application, "0, 612, 0, 6120532", [0, 612, 0, 25940]);
+
+    let resource = VersionResource::read(&application).unwrap();
+
+    assert_eq!(resource.file_version(), Some("0, 612, 0, 6120532"));
+    assert_eq!(resource.product_version(), Some("0, 612, 0, 6120532"));
+    assert_eq!(resource.company_name(), Some("Roblox Corporation"));
+    assert_eq!(resource.string("FileDescription"), None);
+    assert_eq!(resource.fixed_file_version(), Some([0, 612, 0, 25940]));
+    assert_eq!(resource.fixed_product_version(), Some([0, 612, 0, 25940]));
+
+    // the headers are complete, but the resources are cut off
+    let executable = fs::read(&application).unwrap();
+    fs::write(&application, &executable[..0x210]).unwrap();
+    assert!(matches!(
+        VersionResource::read(&application),
+        Err(Error::CorruptExecutable { .. })
+    ));
+
+    fs::write(&application, b"#!/bin/sh\n").unwrap();
+    assert!(matches!(
+        VersionResource::read(&application),
+        Err(Error::CorruptExecutable { .. })
+    ));
+
+    assert!(matches!(
+        VersionResource::read(version.path().join("missing.exe")),
+        Err(Error::VersionUnreadable { .. })
+    ));
+}
+
+#[test]
+fn test_verify() {
+    // the checksum of an empty file
+    const EMPTY_MD5: &str = "d41d8cd98f00b204e9800998ecf8427e";
+
+    let roblox = tempfile::tempdir().unwrap();
+    let versions = roblox.path().join("Versions");
+    create_version(&versions, "version-intact");
+    let version = versions.join("version-intact");
+
+    fs::write(version.join("BuiltInPlugins").join("Plugin.rbxm"), b"12345").unwrap();
+    fs::create_dir_all(version.join("content").join("fonts")).unwrap();
+    fs::write(version.join("content").join("fonts").join("a.ttf"), b"123").unwrap();
+    fs::write(
+        version.join("rbxPkgManifest.txt"),
+        format!(
+            "v0\nRobloxStudio.zip\n{md5}\n0\n0\nBuiltInPlugins.zip\n{md5}\n1\n5\n\
+            content-fonts.zip\n{md5}\n1\n3\n",
+            md5 = EMPTY_MD5
+        ),
+    )
+    .unwrap();
+
+    let studio = RobloxStudio::locate_in(&version, Platform::Windows).unwrap();
+    let report = studio.verify().unwrap();
+
+    assert!(report.is_intact(), "{:?}", report);
+    assert_eq!(report.unchecked_packages(), ["RobloxStudio.zip"]);
+
+    // an interrupted update, with a leftover from a previous version
+    fs::remove_file(version.join("BuiltInPlugins").join("Plugin.rbxm")).unwrap();
+    fs::write(version.join("content").join("fonts").join("a.ttf"), b"1").unwrap();
+    fs::create_dir_all(version.join("content").join("old")).unwrap();
+    fs::write(version.join("RobloxStudio.zip"), b"partial").unwrap();
+
+    let report = studio.verify().unwrap();
+
+    assert_eq!(
+        report.issues(),
+        [
+            IntegrityIssue::ChecksumMismatch {
+                package: "RobloxStudio.zip".to_owned(),
+                expected: EMPTY_MD5.to_owned(),
+                actual: "0e87c1212a698494dcdb198af3e0eb2f".to_owned(),
+            },
+            IntegrityIssue::MissingPackage {
+                package: "BuiltInPlugins.zip".to_owned(),
+                destination: "BuiltInPlugins".into(),
+            },
+            IntegrityIssue::SizeMismatch {
+                package: "content-fonts.zip".to_owned(),
+                destination: Path::new("content").join("fonts"),
+                expected: 3,
+                actual: 1,
+            },
+            IntegrityIssue::ExtraDirectory(Path::new("content").join("old")),
+        ]
+    );
+    assert!(report.unchecked_packages().is_empty());
+
+    fs::write(version.join("rbxPkgManifest.txt"), "v0\nRobloxStudio.zip\n").unwrap();
+    assert!(matches!(
+        studio.verify(),
+        Err(Error::MalformedPackageManifest(_))
+    ));
+
+    fs::remove_file(version.join("rbxPkgManifest.txt")).unwrap();
+    assert!(matches!(
+        studio.verify(),
+        Err(Error::PackageManifestUnreadable { .. })
+    ));
+}
+
+#[test]
+fn test_install() {
+    let build = tempfile::tempdir().unwrap();
+    let executable = build.path().join("RobloxStudioBeta.exe");
+    write_executable(&executable, "0, 612, 0, 6120532", [0, 612, 0, 25940]);
+    let executable = fs::read(executable).unwrap();
+
+    let mirror = tempfile::tempdir().unwrap();
+    write_mirror(
+        mirror.path(),
+        "version-1a2b3c4d",
+        &[
+            ("RobloxStudio.zip", &[("RobloxStudioBeta.exe", &executable)]),
+            // packages built on Windows use backslashes
+            (
+                "BuiltInPlugins.zip",
+                &[(r"Toolbox\Toolbox.rbxm", b"toolbox")],
+            ),
+            ("content-fonts.zip", &[("a.ttf", b"font")]),
+        ],
+    );
+
+    let roblox = tempfile::tempdir().unwrap();
+    let studio = Installer::from_directory(mirror.path(), "version-1a2b3c4d")
+        .install(roblox.path())
+        .unwrap();
+
+    let version = roblox.path().join("Versions").join("version-1a2b3c4d");
+    assert_eq!(studio.root_path(), version);
+    assert_eq!(studio.plugins_path(), roblox.path().join("Plugins"));
+    assert_eq!(
+        fs::read(version.join("BuiltInPlugins").join("Toolbox").join("Toolbox.rbxm")).unwrap(),
+        b"toolbox"
+    );
+    assert_eq!(
+        fs::read(version.join("content").join("fonts").join("a.ttf")).unwrap(),
+        b"font"
+    );
+    assert!(version.join("AppSettings.xml").is_file());
+    assert_eq!(
+        studio.version().unwrap().to_string(),
+        "0.612.0.6120532 (version-1a2b3c4d)"
+    );
+
+    let located = RobloxStudio::locate_in(roblox.path(), Platform::Windows).unwrap();
+    assert_eq!(located.root_path(), version);
+
+    assert!(matches!(
+        Installer::from_directory(mirror.path(), "version-1a2b3c4d").install(roblox.path()),
+        Err(Error::VersionAlreadyInstalled(_))
+    ));
+    assert!(matches!(
+        Installer::from_directory(mirror.path(), "1a2b3c4d").install(roblox.path()),
+        Err(Error::InvalidVersion(_))
+    ));
+
+    // the same packages, without the version prefix, over HTTP
+    let unprefixed = tempfile::tempdir().unwrap();
+    for entry in fs::read_dir(mirror.path()).unwrap() {
+        let entry = entry.unwrap();
+        let name = entry.file_name().into_string().unwrap();
+        let name = name.trim_start_matches("version-1a2b3c4d-");
+        fs::copy(entry.path(), unprefixed.path().join(name)).unwrap();
+    }
+
+    let url = serve_directory(unprefixed.path());
+    let roblox = tempfile::tempdir().unwrap();
+    let studio = Installer::from_mirror(url.as_str(), "version-1a2b3c4d")
+        .install(roblox.path())
+        .unwrap();
+    assert!(studio.verify().unwrap().is_intact());
+
+    // an IPv6 host without a port is reached on port 80, where nothing listens
+    let unreachable = tempfile::tempdir().unwrap();
+    match Installer::from_mirror("http://[::1]/mirror/", "version-1a2b3c4d")
+        .install(unreachable.path())
+    {
+        Err(Error::PackageUnavailable { source, .. }) => {
+            assert_ne!(source.kind(), io::ErrorKind::InvalidInput)
+        }
+        other => panic!("expected an unavailable package, got {:?}", other),
+    }
+
+    assert!(matches!(
+        Installer::from_directory(build.path(), "version-5e6f7a8b").install(roblox.path()),
+        Err(Error::PackageUnavailable { .. })
+    ));
+
+    // a package that doesn't match the manifest is never installed
+    let fonts = mirror.path().join("version-1a2b3c4d-content-fonts.zip");
+    let mut data = fs::read(&fonts).unwrap();
+    data[0] ^= 0xff;
+    fs::write(&fonts, &data).unwrap();
+
+    let roblox = tempfile::tempdir().unwrap();
+    match Installer::from_directory(mirror.path(), "version-1a2b3c4d").install(roblox.path()) {
+        Err(Error::PackageChecksumMismatch { package, .. }) => {
+            assert_eq!(package, "content-fonts.zip")
+        }
+        other => panic!("expected a checksum mismatch, got {:?}", other),
+    }
+    assert_eq!(fs::read_dir(roblox.path()).unwrap().count(), 0);
+
+    // nor is a package cut short
+    fs::write(&fonts, &data[..data.len() - 1]).unwrap();
+
+    match Installer::from_directory(mirror.path(), "version-1a2b3c4d").install(roblox.path()) {
+        Err(Error::PackageUnavailable { package, source }) => {
+            assert_eq!(package, "content-fonts.zip");
+            assert_eq!(source.kind(), io::ErrorKind::UnexpectedEof);
+        }
+        other => panic!("expected an unavailable package, got {:?}", other),
+    }
+    assert_eq!(fs::read_dir(roblox.path()).unwrap().count(), 0);
+
+    // a package escaping its destination
+    write_mirror(
+        mirror.path(),
+        "version-5e6f7a8b",
+        &[("Plugins.zip", &[(r"..\..\evil.txt", b"evil")])],
+    );
+
+    assert!(matches!(
+        Installer::from_directory(mirror.path(), "version-5e6f7a8b").install(roblox.path()),
+        Err(Error::MalformedPackage { .. })
+    ));
+    assert!(!roblox.path().join("evil.txt").exists());
+}
+
+#[test]
+fn test_prune_versions() {
+    let roblox = tempfile::tempdir().unwrap();
+    let versions = roblox.path().join("Versions");
+
+    for (index, name) in ["version-a", "version-b", "version-c", "version-d"]
+        .iter()
+        .enumerate()
+    {
+        create_version(&versions, name);
+        fs::write(versions.join(name).join("content").join("data"), b"1234").unwrap();
+        set_version_modified(&versions, name, 100 * (index as u64 + 1));
+    }
+
+    // an old version is preferred when complete, and incomplete versions are stale
+    fs::write(versions.join("version-d").join("rbxPkgManifest.txt"), "v0\n").unwrap();
+    fs::create_dir_all(versions.join("version-e")).unwrap();
+    fs::write(versions.join("version-e").join("rbxPkgManifest.txt"), "v0\n").unwrap();
+
+    // the Roblox Player shares the `Versions` directory, and has no executable yet while it is
+    // being installed
+    fs::create_dir_all(versions.join("version-player")).unwrap();
+    fs::write(versions.join("version-player").join("RobloxPlayerBeta.exe"), b"").unwrap();
+    fs::create_dir_all(versions.join("version-installing").join("content")).unwrap();
+
+    let studio = RobloxStudio::locate_in(roblox.path(), Platform::Windows).unwrap();
+    assert_eq!(studio.root_path(), versions.join("version-a"));
+
+    let report = studio.prune_versions_dry_run(2).unwrap();
+    let paths = |directories: &[VersionDirectory]| -> Vec<_> {
+        directories
+            .iter()
+            .map(|directory| directory.path().to_owned())
+            .collect()
+    };
+
+    assert!(report.is_dry_run());
+    assert_eq!(
+        paths(report.kept()),
+        [versions.join("version-a"), versions.join("version-d")]
+    );
+    assert_eq!(
+        paths(report.removed()),
+        [
+            versions.join("version-b"),
+            versions.join("version-c"),
+            versions.join("version-e"),
+        ]
+    );
+    assert_eq!(report.removed()[0].size(), 4);
+    assert_eq!(report.freed(), 4 + 4 + 3);
+    assert!(versions.join("version-b").exists());
+
+    let report = studio.prune_versions(0).unwrap();
+    assert!(!report.is_dry_run());
+    assert_eq!(paths(report.kept()), [versions.join("version-a")]);
+    assert_eq!(report.removed().len(), 4);
+    assert_eq!(report.freed(), 3 * 4 + 3 + 3);
+
+    let mut remaining: Vec<_> = fs::read_dir(&versions)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name())
+        .collect();
+    remaining.sort();
+    assert_eq!(
+        remaining,
+        ["version-a", "version-installing", "version-player"]
+    );
+
+    let outside = tempfile::tempdir().unwrap();
+    create_version(outside.path(), "version-a");
+    let studio = RobloxStudio::locate_in(outside.path().join("version-a"), Platform::Windows);
+    assert!(matches!(
+        studio.map(|studio| studio.prune_versions(1)),
+        Ok(Err(Error::NotInVersionsDirectory(_)))
+    ));
+}
+
+#[test]
+fn test_disk_usage() {
+    let roblox = tempfile::tempdir().unwrap();
+    let versions = roblox.path().join("Versions");
+    create_version(&versions, "version-a");
+    create_version(&versions, "version-b");
+
+    let version = versions.join("version-a");
+    fs::write(version.join("RobloxStudioBeta.exe"), b"1").unwrap();
+    fs::write(version.join("content").join("a"), b"12").unwrap();
+    fs::write(version.join("BuiltInPlugins").join("a"), b"123").unwrap();
+    fs::write(versions.join("version-b").join("RobloxStudioBeta.exe"), b"1234").unwrap();
+    set_version_modified(&versions, "version-b", 100);
+
+    for (directory, contents) in [
+        ("Plugins", "12345"),
+        ("logs", "123456"),
+        ("Downloads", "1234567"),
+        ("http", "12345678"),
+    ] {
+        fs::create_dir_all(roblox.path().join(directory)).unwrap();
+        fs::write(roblox.path().join(directory).join("a"), contents).unwrap();
+    }
+
+    let studio = RobloxStudio::locate_in(roblox.path(), Platform::Windows).unwrap();
+    assert_eq!(studio.root_path(), version);
+
+    let usage = studio.disk_usage();
+    assert_eq!(usage.application(), 1);
+    assert_eq!(usage.content(), 2);
+    assert_eq!(usage.built_in_plugins(), 3);
+    assert_eq!(usage.plugins(), 5);
+    assert_eq!(usage.other_versions(), 4);
+    assert_eq!(usage.logs(), 6);
+    assert_eq!(usage.caches(), 15);
+    assert_eq!(usage.total(), 36);
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn test_cli() {
+    use std::process::Command;
+
+    let roblox = tempfile::tempdir().unwrap();
+    let versions = roblox.path().join("Versions");
+    create_version(&versions, "version-a");
+
+    let run = |arguments: &[&str], root: &Path| {
+        Command::new(env!("CARGO_BIN_EXE_roblox-install"))
+            .args(arguments)
+            .env("ROBLOX_STUDIO_PATH", root)
+            .output()
+            .unwrap()
+    };
+
+    let output = run(&["content"], roblox.path());
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap().trim_end(),
+        versions.join("version-a").join("content").to_str().unwrap()
+    );
+
+    let output = run(&["--json", "plugins"], roblox.path());
+    let plugins = roblox.path().join("Plugins");
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap().trim_end(),
+        format!("{:?}", plugins.to_str().unwrap())
+    );
+
+    let output = run(&["locate", "--json"], roblox.path());
+    let json = String::from_utf8(output.stdout).unwrap();
+    assert!(json.starts_with("{\"application\":"), "{}", json);
+    assert!(json.contains("\"built_in_plugins\":"), "{}", json);
+
+    let output = run(&["content"], &roblox.path().join("missing"));
+    assert_eq!(output.status.code(), Some(21));
+
+    let output = run(&["unknown"], roblox.path());
+    assert_eq!(output.status.code(), Some(2));
+
+    let output = run(&["watch"], roblox.path());
+    assert_eq!(output.status.code(), Some(2));
+
+    let source = roblox.path().join("Hello.lua");
+    fs::write(&source, "print('hello')").unwrap();
+    let mut watcher = Command::new(env!("CARGO_BIN_EXE_roblox-install"))
+        .args(["--json", "watch"])
+        .arg(&source)
+        .env("ROBLOX_STUDIO_PATH", roblox.path())
+        .stdout(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut line = String::new();
+    BufReader::new(watcher.stdout.take().unwrap())
+        .read_line(&mut line)
+        .unwrap();
+    watcher.kill().unwrap();
+    watcher.wait().unwrap();
+    assert!(line.starts_with("{\"path\":"), "{}", line);
+    assert!(line.contains("\"number\":1"), "{}", line);
+    assert_eq!(fs::read(plugins.join("Hello.lua")).unwrap(), b"print('hello')");
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde() {
+    use roblox_install::ErrorInfo;
+
+    let roblox = tempfile::tempdir().unwrap();
+    create_version(&roblox.path().join("Versions"), "version-a");
+
+    let studio = RobloxStudio::locate_in(roblox.path(), Platform::Windows).unwrap();
+    let json = serde_json::to_string(&studio).unwrap();
+    let deserialized: RobloxStudio = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized.application_path(), studio.application_path());
+    assert_eq!(deserialized.content_path(), studio.content_path());
+    assert_eq!(deserialized.plugins_path(), studio.plugins_path());
+    assert_eq!(deserialized.source(), studio.source());
+    assert_eq!(deserialized.platform(), Platform::Windows);
+
+    // an installation located on Windows, read elsewhere
+    let json = r#"{
+        "content": "C:\\Users\\Ana\\AppData\\Local\\Roblox\\Versions\\version-a\\content",
+        "application": "C:\\Users\\Ana\\AppData\\Local\\Roblox\\Versions\\version-a\\RobloxStudioBeta.exe",
+        "built_in_plugins": "C:\\Users\\Ana\\AppData\\Local\\Roblox\\Versions\\version-a\\BuiltInPlugins",
+        "plugins": "C:\\Users\\Ana\\AppData\\Local\\Roblox\\Plugins",
+        "root": "C:\\Users\\Ana\\AppData\\Local\\Roblox\\Versions\\version-a",
+        "source": { "Directory": "C:\\Users\\Ana\\AppData\\Local\\Roblox" },
+        "platform": "Windows"
+    }"#;
+    let studio: RobloxStudio = serde_json::from_str(json).unwrap();
+    assert_eq!(
+        studio.plugins_path(),
+        Path::new(r"C:\Users\Ana\AppData\Local\Roblox\Plugins")
+    );
+    assert_eq!(
+        studio.source(),
+        &StudioSource::Directory(r"C:\Users\Ana\AppData\Local\Roblox".into())
+    );
+
+    let round_trip: RobloxStudio =
+        serde_json::from_str(&serde_json::to_string(&studio).unwrap()).unwrap();
+    assert_eq!(round_trip.application_path(), studio.application_path());
+    assert_eq!(round_trip.built_in_plugins_path(), studio.built_in_plugins_path());
+
+    let source = StudioSource::Vinegar {
+        data: "/home/ana/.local/share/vinegar".into(),
+        config: "/home/ana/.config/vinegar/config.toml".into(),
+    };
+    let round_trip: StudioSource =
+        serde_json::from_str(&serde_json::to_string(&source).unwrap()).unwrap();
+    assert_eq!(round_trip, source);
+
+    let version: StudioVersion = "version-1a2b3c4d".parse().unwrap();
+    let round_trip: StudioVersion =
+        serde_json::from_str(&serde_json::to_string(&version).unwrap()).unwrap();
+    assert_eq!(round_trip, version);
+
+    let error = RobloxStudio::locate_in(roblox.path().join("missing"), Platform::Windows)
+        .unwrap_err();
+    let info = ErrorInfo::from(&error);
+    assert_eq!(info.kind(), "StudioDirectoryNotFound");
+    assert_eq!(info.path(), Some(roblox.path().join("missing").as_path()));
+    assert_eq!(info.message(), error.to_string());
+
+    let round_trip: ErrorInfo =
+        serde_json::from_str(&serde_json::to_string(&info).unwrap()).unwrap();
+    assert_eq!(round_trip, info);
+
+    let error = Error::PackageManifestUnreadable {
+        path: r"C:\Roblox\Versions\version-a\rbxPkgManifest.txt".into(),
+        source: io::Error::new(io::ErrorKind::NotFound, "not found"),
+    };
+    let info = ErrorInfo::from(error);
+    assert_eq!(info.causes(), ["not found"]);
+    assert_eq!(
+        info.path(),
+        Some(Path::new(r"C:\Roblox\Versions\version-a\rbxPkgManifest.txt"))
+    );
+}
+
+#[test]
+fn test_install_plugin() {
+    let roblox = tempfile::tempdir().unwrap();
+    create_version(&roblox.path().join("Versions"), "version-a");
+
+    let studio = RobloxStudio::locate_in(roblox.path(), Platform::Windows).unwrap();
+    let plugins = roblox.path().join("Plugins");
+    assert!(!plugins.exists());
+    assert!(studio.list_plugins().unwrap().is_empty());
+
+    let path = studio.install_plugin("Hello.lua", b"print('hello')").unwrap();
+    assert_eq!(path, plugins.join("Hello.lua"));
+    assert_eq!(fs::read(&path).unwrap(), b"print('hello')");
+
+    let built = roblox.path().join("Built.rbxmx");
+    fs::write(&built, "<roblox/>").unwrap();
+    studio.install_plugin("Built.rbxmx", &built).unwrap();
+
+    assert!(matches!(
+        studio.install_plugin("Hello.lua", b"print('again')"),
+        Err(Error::PluginAlreadyInstalled(_))
+    ));
+    assert_eq!(fs::read(&path).unwrap(), b"print('hello')");
+
+    studio.replace_plugin("Hello.lua", &b"print('again')".to_vec()).unwrap();
+    assert_eq!(fs::read(&path).unwrap(), b"print('again')");
+
+    for name in ["../Escape.lua", "Plugin.txt", "", ".hidden.lua", r"..\Escape.lua"] {
+        assert!(
+            matches!(
+                studio.install_plugin(name, b""),
+                Err(Error::InvalidPluginName(_))
+            ),
+            "{}",
+            name
+        );
+    }
+
+    let names: Vec<_> = studio
+        .list_plugins()
+        .unwrap()
+        .iter()
+        .map(|plugin| plugin.name().to_owned())
+        .collect();
+    assert_eq!(names, ["Built.rbxmx", "Hello.lua"]);
+
+    studio.uninstall_plugin("Hello.lua").unwrap();
+    assert!(!path.exists());
+    assert!(matches!(
+        studio.uninstall_plugin("Hello.lua"),
+        Err(Error::PluginNotInstalled(_))
+    ));
+    assert!(matches!(
+        studio.uninstall_plugin("../Versions"),
+        Err(Error::InvalidPluginName(_))
+    ));
+    assert_eq!(studio.list_plugins().unwrap().len(), 1);
+}
+
+#[test]
+fn test_list_plugins() {
+    let roblox = tempfile::tempdir().unwrap();
+    create_version(&roblox.path().join("Versions"), "version-a");
+    let studio = RobloxStudio::locate_in(roblox.path(), Platform::Windows).unwrap();
+
+    // a binary model holding nothing but its `END` chunk
+    let binary_model: &[u8] =
+        b"<roblox!\x89\xff\r\n\x1a\n\0\0\0\0END\0\0\0\0\0\x09\0\0\0\0\0\0\0</roblox>";
+    let xml_model = "\u{feff}<roblox version=\"4\">\n</roblox>\n";
+
+    let plugins = [
+        ("Binary.rbxm", binary_model),
+        ("Xml.rbxmx", xml_model.as_bytes()),
+        ("Script.lua", b"print('hello')"),
+        ("Misnamed.rbxm", xml_model.as_bytes()),
+        ("Truncated.rbxm", &binary_model[..20]),
+        ("Empty.lua", b""),
+        ("Garbage.rbxm", b"\0\x01\x02"),
+    ];
+
+    for (name, contents) in plugins {
+        studio.install_plugin(name, contents).unwrap();
+    }
+
+    fs::create_dir_all(roblox.path().join("Plugins").join("Folder")).unwrap();
+    fs::write(
+        roblox.path().join("Plugins").join("Folder").join("init.lua"),
+        "return 1",
+    )
+    .unwrap();
+
+    let listed = studio.list_plugins().unwrap();
+    let find = |name: &str| {
+        listed
+            .iter()
+            .find(|plugin| plugin.name() == name)
+            .unwrap_or_else(|| panic!("{} is not listed", name))
+    };
+
+    let binary = find("Binary.rbxm");
+    assert_eq!(binary.kind(), PluginKind::BinaryModel);
+    assert_eq!(binary.size(), binary_model.len() as u64);
+    assert_eq!(
+        binary.hash(),
+        Some(format!("{:x}", md5::compute(binary_model)).as_str())
+    );
+    assert!(binary.modified().is_some());
+    assert!(!binary.is_malformed(), "{:?}", binary.issues());
+
+    assert_eq!(find("Xml.rbxmx").kind(), PluginKind::XmlModel);
+    assert!(!find("Xml.rbxmx").is_malformed());
+    assert_eq!(find("Script.lua").kind(), PluginKind::Script);
+    assert!(!find("Script.lua").is_malformed());
+
+    let folder = find("Folder");
+    assert_eq!(folder.kind(), PluginKind::Folder);
+    assert_eq!(folder.size(), 8);
+    assert_eq!(folder.hash(), None);
+
+    let misnamed = find("Misnamed.rbxm");
+    assert_eq!(misnamed.kind(), PluginKind::XmlModel);
+    assert_eq!(
+        misnamed.issues(),
+        [PluginIssue::ExtensionMismatch {
+            extension: "rbxm".to_owned()
+        }]
+    );
+
+    assert_eq!(find("Truncated.rbxm").issues(), [PluginIssue::Truncated]);
+    assert_eq!(find("Empty.lua").issues(), [PluginIssue::Empty]);
+    assert_eq!(find("Garbage.rbxm").kind(), PluginKind::Unknown);
+    assert_eq!(find("Garbage.rbxm").issues(), [PluginIssue::UnknownFormat]);
+}
+
+#[test]
+fn test_sync_plugins() {
+    let roblox = tempfile::tempdir().unwrap();
+    create_version(&roblox.path().join("Versions"), "version-a");
+    let studio = RobloxStudio::locate_in(roblox.path(), Platform::Windows).unwrap();
+    let plugins = roblox.path().join("Plugins");
+
+    let project = tempfile::tempdir().unwrap();
+    let manifest = project.path().join("roblox-plugins.toml");
+    let lockfile = project.path().join("roblox-plugins.lock");
+    fs::create_dir_all(project.path().join("plugins")).unwrap();
+    fs::write(project.path().join("plugins/Hello.lua"), "print('hello')").unwrap();
+    fs::write(project.path().join("Model.rbxmx"), "<roblox/>").unwrap();
+    fs::write(
+        &manifest,
+        "[plugins]\n\"Hello.lua\" = \"plugins/Hello.lua\"\n\"Model.rbxmx\" = \"Model.rbxmx\"\n",
+    )
+    .unwrap();
+
+    let parsed = PluginManifest::read(&manifest).unwrap();
+    let names: Vec<_> = parsed.plugins().map(|(name, _)| name).collect();
+    assert_eq!(names, ["Hello.lua", "Model.rbxmx"]);
+
+    let report = studio.sync_plugins(&manifest).unwrap();
+    assert_eq!(
+        report.changes(),
+        [
+            PluginChange::Installed("Hello.lua".to_owned()),
+            PluginChange::Installed("Model.rbxmx".to_owned()),
+        ]
+    );
+    assert_eq!(
+        fs::read_to_string(plugins.join("Hello.lua")).unwrap(),
+        "print('hello')"
+    );
+    assert!(lockfile.is_file());
+
+    // running it again changes nothing, not even the lockfile
+    let locked = fs::metadata(&lockfile).unwrap().modified().unwrap();
+    thread::sleep(Duration::from_millis(10));
+    let report = studio.sync_plugins(&manifest).unwrap();
+    assert!(report.is_unchanged(), "{}", report);
+    assert_eq!(fs::metadata(&lockfile).unwrap().modified().unwrap(), locked);
+
+    fs::write(project.path().join("plugins/Hello.lua"), "print('again')").unwrap();
+    let report = studio.sync_plugins(&manifest).unwrap();
+    assert_eq!(
+        report.changes(),
+        [
+            PluginChange::Updated("Hello.lua".to_owned()),
+            PluginChange::Unchanged("Model.rbxmx".to_owned()),
+        ]
+    );
+    assert_eq!(
+        fs::read_to_string(plugins.join("Hello.lua")).unwrap(),
+        "print('again')"
+    );
+
+    // a plugin this manifest didn't install is never overwritten
+    studio.install_plugin("Other.lua", b"print('mine')").unwrap();
+    fs::write(project.path().join("Other.lua"), "print('theirs')").unwrap();
+    fs::write(
+        &manifest,
+        "[plugins]\n\"Hello.lua\" = \"plugins/Hello.lua\"\n\"Other.lua\" = \"Other.lua\"\n",
+    )
+    .unwrap();
+    assert!(matches!(
+        studio.sync_plugins(&manifest),
+        Err(Error::PluginConflict(path)) if path == plugins.join("Other.lua")
+    ));
+    assert_eq!(
+        fs::read_to_string(plugins.join("Other.lua")).unwrap(),
+        "print('mine')"
+    );
+    assert!(plugins.join("Model.rbxmx").exists());
+
+    // delisted plugins are removed, unless they were modified
+    fs::write(&manifest, "[plugins]\n").unwrap();
+    fs::write(plugins.join("Hello.lua"), "print('modified')").unwrap();
+    let report = studio.sync_plugins(&manifest).unwrap();
+    assert_eq!(
+        report.changes(),
+        [
+            PluginChange::Released("Hello.lua".to_owned()),
+            PluginChange::Removed("Model.rbxmx".to_owned()),
+        ]
+    );
+    assert!(plugins.join("Hello.lua").exists());
+    assert!(!plugins.join("Model.rbxmx").exists());
+    assert!(plugins.join("Other.lua").exists());
+    assert!(studio.sync_plugins(&manifest).unwrap().changes().is_empty());
+
+    let missing = project.path().join("Missing.lua");
+    fs::write(&manifest, "[plugins]\n\"Missing.lua\" = \"Missing.lua\"\n").unwrap();
+    assert!(matches!(
+        studio.sync_plugins(&manifest),
+        Err(Error::PluginSourceUnreadable { path, .. }) if path == missing
+    ));
+
+    fs::write(&manifest, "plugins = 1\n").unwrap();
+    assert!(matches!(
+        studio.sync_plugins(&manifest),
+        Err(Error::MalformedPluginManifest(_))
+    ));
+    assert!(matches!(
+        studio.sync_plugins(project.path().join("missing.toml")),
+        Err(Error::PluginManifestUnreadable { .. })
+    ));
+}
+
+#[test]
+fn test_sync_plugins_interrupted() {
+    let roblox = tempfile::tempdir().unwrap();
+    create_version(&roblox.path().join("Versions"), "version-a");
+    let studio = RobloxStudio::locate_in(roblox.path(), Platform::Windows).unwrap();
+    let plugins = roblox.path().join("Plugins");
+
+    // a plugin whose name fits in a file name, but not once made into the name of its
+    // temporary file, fails to install after `A.lua` was installed and before `B.lua` is
+    let blocker = format!("A{}.lua", "0".repeat(245));
+
+    let project = tempfile::tempdir().unwrap();
+    let manifest = project.path().join("roblox-plugins.toml");
+    let write_manifest = |names: &[&str]| {
+        let mut text = "[plugins]\n".to_owned();
+        for name in names {
+            text += &format!("\"{}\" = \"{}\"\n", name, name);
+        }
+        fs::write(&manifest, text).unwrap();
+    };
+
+    fs::write(project.path().join("A.lua"), "print('a')").unwrap();
+    fs::write(project.path().join(&blocker), "print('blocker')").unwrap();
+    fs::write(project.path().join("B.lua"), "print('b')").unwrap();
+    write_manifest(&["A.lua", &blocker, "B.lua"]);
+
+    assert!(matches!(
+        studio.sync_plugins(&manifest),
+        Err(Error::PluginInstallFailed { .. })
+    ));
+    assert!(plugins.join("A.lua").is_file());
+    assert!(!plugins.join("B.lua").exists());
+
+    write_manifest(&["A.lua", "B.lua"]);
+    let report = studio.sync_plugins(&manifest).unwrap();
+    assert_eq!(
+        report.changes(),
+        [
+            PluginChange::Unchanged("A.lua".to_owned()),
+            PluginChange::Removed(blocker.clone()),
+            PluginChange::Installed("B.lua".to_owned()),
+        ]
+    );
+
+    // an update interrupted the same way, with the source changing again before the next sync
+    fs::write(project.path().join("A.lua"), "print('a2')").unwrap();
+    fs::write(project.path().join("B.lua"), "print('b2')").unwrap();
+    write_manifest(&["A.lua", &blocker, "B.lua"]);
+    assert!(studio.sync_plugins(&manifest).is_err());
+    assert_eq!(
+        fs::read_to_string(plugins.join("A.lua")).unwrap(),
+        "print('a2')"
+    );
+    assert_eq!(
+        fs::read_to_string(plugins.join("B.lua")).unwrap(),
+        "print('b')"
+    );
+
+    write_manifest(&["A.lua", "B.lua"]);
+    fs::write(project.path().join("A.lua"), "print('a3')").unwrap();
+    let report = studio.sync_plugins(&manifest).unwrap();
+    assert_eq!(
+        report.changes(),
+        [
+            PluginChange::Updated("A.lua".to_owned()),
+            PluginChange::Removed(blocker.clone()),
+            PluginChange::Updated("B.lua".to_owned()),
+        ]
+    );
+    assert_eq!(
+        fs::read_to_string(plugins.join("A.lua")).unwrap(),
+        "print('a3')"
+    );
+    assert_eq!(
+        fs::read_to_string(plugins.join("B.lua")).unwrap(),
+        "print('b2')"
+    );
+
+    let lockfile = fs::read_to_string(project.path().join("roblox-plugins.lock")).unwrap();
+    assert!(!lockfile.contains("previous"), "{}", lockfile);
+    assert!(studio.sync_plugins(&manifest).unwrap().is_unchanged());
+
+    // an interrupted update of a plugin that is then taken out of the manifest still removes it
+    fs::write(project.path().join("B.lua"), "print('b3')").unwrap();
+    write_manifest(&["A.lua", &blocker, "B.lua"]);
+    assert!(studio.sync_plugins(&manifest).is_err());
+    assert_eq!(
+        fs::read_to_string(plugins.join("B.lua")).unwrap(),
+        "print('b2')"
+    );
+
+    write_manifest(&["A.lua"]);
+    let report = studio.sync_plugins(&manifest).unwrap();
+    assert_eq!(
+        report.changes(),
+        [
+            PluginChange::Unchanged("A.lua".to_owned()),
+            PluginChange::Removed(blocker.clone()),
+            PluginChange::Removed("B.lua".to_owned()),
+        ]
+    );
+    assert!(!plugins.join("B.lua").exists());
+}
+
+#[test]
+fn test_watch_plugin() {
+    let roblox = tempfile::tempdir().unwrap();
+    create_version(&roblox.path().join("Versions"), "version-a");
+    let studio = RobloxStudio::locate_in(roblox.path(), Platform::Windows).unwrap();
+    let plugins = roblox.path().join("Plugins");
+
+    let project = tempfile::tempdir().unwrap();
+    let source = project.path().join("MyPlugin.rbxmx");
+
+    let mut watcher = studio
+        .watch_plugin("MyPlugin.rbxmx", &source)
+        .unwrap()
+        .debounce(Duration::from_millis(200));
+    assert_eq!(watcher.plugin_path(), plugins.join("MyPlugin.rbxmx"));
+
+    // nothing is deployed until the source exists and stops changing
+    assert_eq!(watcher.poll().unwrap(), None);
+    fs::write(&source, "<roblox>").unwrap();
+    assert_eq!(watcher.poll().unwrap(), None);
+    fs::write(&source, "<roblox></roblox>").unwrap();
+    assert_eq!(watcher.poll().unwrap(), None);
+    assert!(!plugins.join("MyPlugin.rbxmx").exists());
+
+    thread::sleep(Duration::from_millis(250));
+    let deploy = watcher.poll().unwrap().unwrap();
+    assert_eq!(deploy.path(), plugins.join("MyPlugin.rbxmx"));
+    assert_eq!(deploy.size(), 17);
+    assert_eq!(deploy.number(), 1);
+    assert_eq!(deploy.to_string(), "deployed MyPlugin.rbxmx (17 bytes, #1)");
+    assert_eq!(
+        fs::read_to_string(deploy.path()).unwrap(),
+        "<roblox></roblox>"
+    );
+
+    thread::sleep(Duration::from_millis(250));
+    assert_eq!(watcher.poll().unwrap(), None);
+
+    fs::write(&source, "<roblox>\n</roblox>").unwrap();
+    let mut deploys = Vec::new();
+    watcher
+        .interval(Duration::from_millis(10))
+        .debounce(Duration::ZERO)
+        .watch(|event| {
+            deploys.push(event.unwrap());
+            false
+        });
+    assert_eq!(deploys.len(), 1);
+    assert_eq!(deploys[0].number(), 2);
+    assert_eq!(
+        fs::read_to_string(plugins.join("MyPlugin.rbxmx")).unwrap(),
+        "<roblox>\n</roblox>"
+    );
+
+    // directories are deployed as plugin folders, replacing the previous deploy
+    let folder = project.path().join("Folder");
+    fs::create_dir_all(folder.join("modules")).unwrap();
+    fs::write(folder.join("init.lua"), "return 1").unwrap();
+    fs::write(folder.join("modules").join("Old.lua"), "return 2").unwrap();
+
+    let mut watcher = studio
+        .watch_plugin("Folder", &folder)
+        .unwrap()
+        .debounce(Duration::ZERO);
+    let deploy = watcher.poll().unwrap().unwrap();
+    assert_eq!(deploy.size(), 16);
+    let modules = plugins.join("Folder").join("modules");
+    assert!(modules.join("Old.lua").is_file());
+
+    // a previous deploy left by a failed swap is removed by the next deploy, but not the one
+    // of another plugin starting with the same name
+    let stranded = plugins.join(".Folder.1.0.old");
+    let other = plugins.join(".Folder.v2.1.0.old");
+    fs::create_dir_all(stranded.join("modules")).unwrap();
+    fs::create_dir_all(&other).unwrap();
+
+    fs::remove_file(folder.join("modules").join("Old.lua")).unwrap();
+    fs::write(folder.join("modules").join("New.lua"), "return 3").unwrap();
+    assert_eq!(watcher.poll().unwrap().unwrap().number(), 2);
+    assert!(!modules.join("Old.lua").exists());
+    assert!(modules.join("New.lua").is_file());
+    assert!(!stranded.exists());
+    assert!(other.exists());
+    fs::remove_dir(&other).unwrap();
+
+    let names: Vec<_> = fs::read_dir(&plugins)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name())
+        .collect();
+    assert_eq!(names.len(), 2, "{:?}", names);
+
+    assert!(matches!(
+        studio.watch_plugin("MyPlugin.txt", &source),
+        Err(Error::InvalidPluginName(_))
+    ));
+    assert!(matches!(
+        studio.watch_plugin("../Folder", &folder),
+        Err(Error::InvalidPluginName(_))
+    ));
+}
+
+#[test]
+fn test_plugin_model() {
+    let project = tempfile::tempdir().unwrap();
+    let script = project.path().join("Hello.server.lua");
+    fs::write(&script, "print(\"a]]>b\") -- <&>\n").unwrap();
+
+    let model = PluginModel::read(&script).unwrap();
+    assert_eq!(model.root().class(), InstanceClass::Script);
+    assert_eq!(model.root().name(), "Hello");
+    assert_eq!(
+        model.to_rbxmx(),
+        concat!(
+            "<roblox xmlns:xmime=\"http://www.w3.org/2005/05/xmlmime\" ",
+            "xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" ",
+            "xsi:noNamespaceSchemaLocation=\"http://www.roblox.com/roblox.xsd\" version=\"4\">\n",
+            "\t<External>null</External>\n",
+            "\t<External>nil</External>\n",
+            "\t<Item class=\"Script\" referent=\"RBX0\">\n",
+            "\t\t<Properties>\n",
+            "\t\t\t<string name=\"Name\">Hello</string>\n",
+            "\t\t\t<ProtectedString name=\"Source\">",
+            "<![CDATA[print(\"a]]]]><![CDATA[>b\") -- <&>\n]]></ProtectedString>\n",
+            "\t\t</Properties>\n",
+            "\t</Item>\n",
+            "</roblox>\n",
+        )
+    );
+
+    let plugin = project.path().join("My & Plugin");
+    fs::create_dir_all(plugin.join("modules").join("Empty")).unwrap();
+    fs::write(plugin.join("init.server.luau"), "require(script.modules.Util)").unwrap();
+    fs::write(plugin.join("init.lua"), "return nil").unwrap();
+    fs::write(plugin.join("README.md"), "# My Plugin").unwrap();
+    fs::write(plugin.join(".hidden.lua"), "return nil").unwrap();
+    fs::write(plugin.join("modules").join("Util.lua"), "return {}").unwrap();
+    fs::write(plugin.join("modules").join("Run.server.lua"), "print(1)").unwrap();
+    fs::create_dir_all(plugin.join("Widget")).unwrap();
+    fs::write(plugin.join("Widget").join("init.luau"), "return 1").unwrap();
+    fs::write(plugin.join("modules").join("Gui.client.lua"), "print(2)").unwrap();
+    fs::create_dir_all(plugin.join("Hud")).unwrap();
+    fs::write(plugin.join("Hud").join("init.lua"), "return 2").unwrap();
+    fs::write(plugin.join("Hud").join("init.client.lua"), "print(3)").unwrap();
+
+    let model = PluginModel::read(&plugin).unwrap();
+    let root = model.root();
+    assert_eq!(root.class(), InstanceClass::Script);
+    assert_eq!(root.name(), "My & Plugin");
+    assert_eq!(root.source(), Some("require(script.modules.Util)"));
+
+    let children: Vec<_> = root
+        .children()
+        .iter()
+        .map(|child| (child.class(), child.name()))
+        .collect();
+    assert_eq!(
+        children,
+        [
+            (InstanceClass::LocalScript, "Hud"),
+            (InstanceClass::ModuleScript, "Widget"),
+            (InstanceClass::Folder, "modules"),
+        ]
+    );
+
+    assert_eq!(root.children()[0].source(), Some("print(3)"));
+
+    let modules: Vec<_> = root.children()[2]
+        .children()
+        .iter()
+        .map(|child| (child.class(), child.name()))
+        .collect();
+    assert_eq!(
+        modules,
+        [
+            (InstanceClass::Folder, "Empty"),
+            (InstanceClass::LocalScript, "Gui"),
+            (InstanceClass::Script, "Run"),
+            (InstanceClass::ModuleScript, "Util"),
+        ]
+    );
+
+    let document = model.to_rbxmx();
+    assert!(document.contains("<string name=\"Name\">My &amp; Plugin</string>"));
+    assert!(document.contains("<Item class=\"LocalScript\" referent=\"RBX1\">"));
+    assert!(document.contains("referent=\"RBX7\""));
+    assert!(!document.contains("README"));
+    assert_eq!(PluginModel::read(&plugin).unwrap().to_rbxmx(), document);
+
+    let roblox = tempfile::tempdir().unwrap();
+    create_version(&roblox.path().join("Versions"), "version-a");
+    let studio = RobloxStudio::locate_in(roblox.path(), Platform::Windows).unwrap();
+
+    let path = studio.install_plugin("MyPlugin.rbxmx", &model).unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), document);
+    let plugins = studio.list_plugins().unwrap();
+    assert_eq!(plugins[0].kind(), PluginKind::XmlModel);
+    assert!(!plugins[0].is_malformed(), "{:?}", plugins[0].issues());
+
+    let script = PluginModel::script("Hello", "print('hello')");
+    assert!(matches!(
+        studio.install_plugin("Hello.lua", &script),
+        Err(Error::InvalidPluginName(_))
+    ));
+
+    assert!(matches!(
+        PluginModel::read(plugin.join("README.md")),
+        Err(Error::NotAScript(_))
+    ));
+
+    // a link to a directory is skipped, as it could lead back to the directory holding it
+    #[cfg(unix)]
+    {
+        std::os::unix::fs::symlink(&plugin, plugin.join("Loop")).unwrap();
+        assert_eq!(PluginModel::read(&plugin).unwrap().to_rbxmx(), document);
+    }
+
+    // macOS doesn't allow file names that aren't valid UTF-8
+    #[cfg(target_os = "linux")]
+    {
+        use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+        let name = plugin.join(OsStr::from_bytes(b"Bad\xff.lua"));
+        fs::write(&name, "print(1)").unwrap();
+        assert!(matches!(
+            PluginModel::read(&plugin),
+            Err(Error::PluginSourceUnreadable { path, .. }) if path == name
+        ));
+        fs::remove_file(&name).unwrap();
+    }
+
+    fs::write(plugin.join("Broken.lua"), b"\xff\xfe").unwrap();
+    assert!(matches!(
+        PluginModel::read(&plugin),
+        Err(Error::PluginSourceUnreadable { path, .. }) if path == plugin.join("Broken.lua")
+    ));
+}